@@ -0,0 +1,7 @@
+-- Create "rotated_refresh_secrets" table
+CREATE TABLE "public"."rotated_refresh_secrets" (
+  "secret" uuid NOT NULL,
+  "user_id" bigint NOT NULL,
+  PRIMARY KEY ("secret"),
+  CONSTRAINT "rotated_refresh_secrets_user_id_fkey" FOREIGN KEY ("user_id") REFERENCES "public"."users" ("id") ON UPDATE NO ACTION ON DELETE CASCADE
+);
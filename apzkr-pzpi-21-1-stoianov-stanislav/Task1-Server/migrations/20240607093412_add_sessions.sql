@@ -0,0 +1,35 @@
+-- Create "sessions" table
+CREATE TABLE "public"."sessions" (
+  "id" bigserial NOT NULL,
+  "user_id" bigint NOT NULL,
+  "refresh_secret" uuid NOT NULL,
+  "device_label" character varying(50) NOT NULL,
+  "user_agent" character varying(255) NOT NULL,
+  "created_at" timestamptz NOT NULL,
+  "last_used_at" timestamptz NOT NULL,
+  PRIMARY KEY ("id"),
+  CONSTRAINT "sessions_refresh_secret_key" UNIQUE ("refresh_secret"),
+  CONSTRAINT "sessions_user_id_fkey" FOREIGN KEY ("user_id") REFERENCES "public"."users" ("id") ON UPDATE NO ACTION ON DELETE CASCADE
+);
+
+INSERT INTO "public"."sessions"
+  ("user_id", "refresh_secret", "device_label", "user_agent", "created_at", "last_used_at")
+SELECT "id", "refresh_secret", '', '', now(), now()
+FROM "public"."users";
+
+-- Modify "rotated_refresh_secrets" table
+ALTER TABLE "public"."rotated_refresh_secrets"
+ADD COLUMN "session_id" bigint NULL;
+
+UPDATE "public"."rotated_refresh_secrets" AS "r"
+SET "session_id" = "s"."id"
+FROM "public"."sessions" AS "s"
+WHERE "s"."user_id" = "r"."user_id";
+
+ALTER TABLE "public"."rotated_refresh_secrets"
+DROP COLUMN "user_id",
+ALTER COLUMN "session_id" SET NOT NULL,
+ADD CONSTRAINT "rotated_refresh_secrets_session_id_fkey" FOREIGN KEY ("session_id") REFERENCES "public"."sessions" ("id") ON UPDATE NO ACTION ON DELETE CASCADE;
+
+-- Modify "users" table
+ALTER TABLE "public"."users" DROP COLUMN "refresh_secret";
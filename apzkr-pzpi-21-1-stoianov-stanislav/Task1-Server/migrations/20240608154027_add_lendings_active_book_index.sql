@@ -0,0 +1,17 @@
+-- Modify "lendings" table
+ALTER TABLE "public"."lendings" ADD COLUMN "closed_by_migration" boolean NOT NULL DEFAULT false;
+
+-- Close all but the latest open lending of each book, flagging them so later
+-- backfills do not treat them as real returns
+UPDATE "public"."lendings" AS "l"
+SET ("returned_on", "closed_by_migration") = ("l"."lent_on", true)
+WHERE "l"."returned_on" IS NULL
+  AND EXISTS (
+    SELECT FROM "public"."lendings" AS "n"
+    WHERE "n"."book_id" = "l"."book_id"
+      AND "n"."returned_on" IS NULL
+      AND "n"."id" > "l"."id"
+  );
+
+-- Create index "lendings_active_book_id_key" to table: "lendings"
+CREATE UNIQUE INDEX "lendings_active_book_id_key" ON "public"."lendings" ("book_id") WHERE (returned_on IS NULL);
@@ -0,0 +1,14 @@
+-- Modify "lendings" table
+ALTER TABLE "public"."lendings"
+ADD COLUMN "lending_fee" numeric(10,2) NULL,
+ADD COLUMN "overdue_fee" numeric(10,2) NULL;
+
+UPDATE "public"."lendings" AS "l"
+SET ("lending_fee", "overdue_fee")
+  = (round(greatest("l"."returned_on" - "l"."lent_on", 1) * "lib"."daily_rate", 2),
+     round(greatest("l"."returned_on" - "l"."due", 0) * "lib"."daily_rate" * "lib"."overdue_rate", 2))
+FROM "public"."books" AS "b", "public"."libraries" AS "lib"
+WHERE "b"."id" = "l"."book_id"
+  AND "lib"."id" = "b"."library_id"
+  AND "l"."returned_on" IS NOT NULL
+  AND NOT "l"."closed_by_migration";
@@ -0,0 +1,34 @@
+-- Modify "libraries" table
+ALTER TABLE "public"."libraries" ADD COLUMN "balance_limit" numeric(10,2) NULL;
+-- Create "ledger_entries" table
+CREATE TABLE "public"."ledger_entries" (
+  "id" bigserial NOT NULL,
+  "user_id" bigint NOT NULL,
+  "library_id" bigint NOT NULL,
+  "lending_id" bigint NULL,
+  "kind" character varying(32) NOT NULL,
+  "amount" numeric(10,2) NOT NULL,
+  "currency" character varying(3) NOT NULL,
+  "recorded_at" timestamptz NOT NULL,
+  PRIMARY KEY ("id"),
+  CONSTRAINT "ledger_entries_lending_id_fkey" FOREIGN KEY ("lending_id") REFERENCES "public"."lendings" ("id") ON UPDATE NO ACTION ON DELETE SET NULL,
+  CONSTRAINT "ledger_entries_library_id_fkey" FOREIGN KEY ("library_id") REFERENCES "public"."libraries" ("id") ON UPDATE NO ACTION ON DELETE CASCADE,
+  CONSTRAINT "ledger_entries_user_id_fkey" FOREIGN KEY ("user_id") REFERENCES "public"."users" ("id") ON UPDATE NO ACTION ON DELETE CASCADE,
+  CONSTRAINT "ledger_entries_currency_check" CHECK ((currency)::text = ANY ((ARRAY['UAH'::character varying, 'USD'::character varying, 'EUR'::character varying])::text[])),
+  CONSTRAINT "ledger_entries_kind_check" CHECK ((kind)::text = ANY ((ARRAY['lending_charge'::character varying, 'overdue_penalty'::character varying, 'adjustment'::character varying, 'payment'::character varying])::text[]))
+);
+
+INSERT INTO "public"."ledger_entries"
+  ("user_id", "library_id", "lending_id", "kind", "amount", "currency", "recorded_at")
+SELECT "l"."lendee_id", "lib"."id", "l"."id", "c"."kind", "c"."amount", "lib"."currency", "l"."returned_on"
+FROM "public"."lendings" AS "l"
+JOIN "public"."books" AS "b" ON "b"."id" = "l"."book_id"
+JOIN "public"."libraries" AS "lib" ON "lib"."id" = "b"."library_id"
+CROSS JOIN LATERAL (
+  VALUES ('lending_charge', "l"."lending_fee"), ('overdue_penalty', "l"."overdue_fee")
+) AS "c" ("kind", "amount")
+WHERE "c"."amount" > 0
+  AND NOT "l"."closed_by_migration";
+
+-- Create index "ledger_entries_user_id_library_id_idx" to table: "ledger_entries"
+CREATE INDEX "ledger_entries_user_id_library_id_idx" ON "public"."ledger_entries" ("user_id", "library_id");
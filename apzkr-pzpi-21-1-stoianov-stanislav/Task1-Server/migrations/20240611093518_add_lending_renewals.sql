@@ -0,0 +1,16 @@
+-- Modify "libraries" table
+ALTER TABLE "public"."libraries" ADD COLUMN "renewal_period" smallint NOT NULL DEFAULT 14, ADD COLUMN "max_renewals" smallint NOT NULL DEFAULT 2;
+-- Modify "lendings" table
+ALTER TABLE "public"."lendings" ADD COLUMN "renewals" smallint NOT NULL DEFAULT 0;
+-- Create "lending_renewals" table
+CREATE TABLE "public"."lending_renewals" (
+  "id" bigserial NOT NULL,
+  "lending_id" bigint NOT NULL,
+  "renewed_on" timestamptz NOT NULL,
+  "previous_due" date NOT NULL,
+  "new_due" date NOT NULL,
+  PRIMARY KEY ("id"),
+  CONSTRAINT "lending_renewals_lending_id_fkey" FOREIGN KEY ("lending_id") REFERENCES "public"."lendings" ("id") ON UPDATE NO ACTION ON DELETE CASCADE
+);
+-- Create index "lending_renewals_lending_id_idx" to table: "lending_renewals"
+CREATE INDEX "lending_renewals_lending_id_idx" ON "public"."lending_renewals" ("lending_id");
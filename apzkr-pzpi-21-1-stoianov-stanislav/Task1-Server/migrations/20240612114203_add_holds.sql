@@ -0,0 +1,17 @@
+-- Create "holds" table
+CREATE TABLE "public"."holds" (
+  "id" bigserial NOT NULL,
+  "book_id" bigint NOT NULL,
+  "user_id" bigint NOT NULL,
+  "status" character varying(32) NOT NULL,
+  "placed_at" timestamptz NOT NULL,
+  "ready_until" date NULL,
+  PRIMARY KEY ("id"),
+  CONSTRAINT "holds_book_id_fkey" FOREIGN KEY ("book_id") REFERENCES "public"."books" ("id") ON UPDATE NO ACTION ON DELETE CASCADE,
+  CONSTRAINT "holds_user_id_fkey" FOREIGN KEY ("user_id") REFERENCES "public"."users" ("id") ON UPDATE NO ACTION ON DELETE CASCADE,
+  CONSTRAINT "holds_status_check" CHECK ((status)::text = ANY ((ARRAY['waiting'::character varying, 'ready'::character varying, 'fulfilled'::character varying, 'cancelled'::character varying, 'expired'::character varying])::text[]))
+);
+-- Create index "holds_active_book_id_user_id_key" to table: "holds"
+CREATE UNIQUE INDEX "holds_active_book_id_user_id_key" ON "public"."holds" ("book_id", "user_id") WHERE ((status)::text = ANY ((ARRAY['waiting'::character varying, 'ready'::character varying])::text[]));
+-- Create index "holds_ready_book_id_key" to table: "holds"
+CREATE UNIQUE INDEX "holds_ready_book_id_key" ON "public"."holds" ("book_id") WHERE ((status)::text = 'ready'::text);
@@ -0,0 +1,37 @@
+-- Create "copies" table
+CREATE TABLE "public"."copies" (
+  "id" bigserial NOT NULL,
+  "book_id" bigint NOT NULL,
+  "barcode" character varying(32) NOT NULL,
+  "shelf_location" character varying(50) NOT NULL,
+  "condition" character varying(32) NOT NULL,
+  PRIMARY KEY ("id"),
+  CONSTRAINT "copies_barcode_key" UNIQUE ("barcode"),
+  CONSTRAINT "copies_book_id_fkey" FOREIGN KEY ("book_id") REFERENCES "public"."books" ("id") ON UPDATE NO ACTION ON DELETE CASCADE,
+  CONSTRAINT "copies_condition_check" CHECK ((condition)::text = ANY ((ARRAY['new'::character varying, 'good'::character varying, 'fair'::character varying, 'poor'::character varying, 'damaged'::character varying])::text[]))
+);
+-- Create index "copies_book_id_idx" to table: "copies"
+CREATE INDEX "copies_book_id_idx" ON "public"."copies" ("book_id");
+
+INSERT INTO "public"."copies"
+  ("id", "book_id", "barcode", "shelf_location", "condition")
+SELECT "id", "id", 'C' || lpad("id"::text, 9, '0'), '', 'good'
+FROM "public"."books";
+
+SELECT setval('"public"."copies_id_seq"', coalesce(max("id"), 0) + 1, false)
+FROM "public"."copies";
+
+-- Modify "lendings" table
+ALTER TABLE "public"."lendings" ADD COLUMN "copy_id" bigint NULL;
+
+UPDATE "public"."lendings" SET "copy_id" = "book_id";
+
+ALTER TABLE "public"."lendings" ALTER COLUMN "copy_id" SET NOT NULL, ADD CONSTRAINT "lendings_copy_id_fkey" FOREIGN KEY ("copy_id") REFERENCES "public"."copies" ("id") ON UPDATE NO ACTION ON DELETE CASCADE;
+-- Drop index "lendings_active_book_id_key" from table: "lendings"
+DROP INDEX "public"."lendings_active_book_id_key";
+-- Modify "lendings" table
+ALTER TABLE "public"."lendings" DROP COLUMN "book_id";
+-- Create index "lendings_active_copy_id_key" to table: "lendings"
+CREATE UNIQUE INDEX "lendings_active_copy_id_key" ON "public"."lendings" ("copy_id") WHERE (returned_on IS NULL);
+-- Drop index "holds_ready_book_id_key" from table: "holds"
+DROP INDEX "public"."holds_ready_book_id_key";
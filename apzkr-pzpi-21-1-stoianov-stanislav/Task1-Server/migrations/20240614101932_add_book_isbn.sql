@@ -0,0 +1,6 @@
+-- Modify "books" table
+ALTER TABLE "public"."books" ADD COLUMN "isbn" character varying(13) NULL;
+-- Create index "books_library_id_isbn_key" to table: "books"
+CREATE UNIQUE INDEX "books_library_id_isbn_key" ON "public"."books" ("library_id", "isbn");
+-- Create index "books_isbn_idx" to table: "books"
+CREATE INDEX "books_isbn_idx" ON "public"."books" ("isbn");
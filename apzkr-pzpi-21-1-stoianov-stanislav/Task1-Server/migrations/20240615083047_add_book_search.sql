@@ -0,0 +1,4 @@
+-- Modify "books" table
+ALTER TABLE "public"."books" ADD COLUMN "search" tsvector NOT NULL GENERATED ALWAYS AS ((setweight(to_tsvector('simple'::regconfig, (name)::text), 'A'::"char") || setweight(to_tsvector('simple'::regconfig, (author)::text), 'B'::"char")) || setweight(to_tsvector('simple'::regconfig, (genre)::text), 'C'::"char")) STORED;
+-- Create index "books_search_idx" to table: "books"
+CREATE INDEX "books_search_idx" ON "public"."books" USING gin ("search");
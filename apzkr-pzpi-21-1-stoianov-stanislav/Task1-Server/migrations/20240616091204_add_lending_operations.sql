@@ -0,0 +1,6 @@
+-- Modify "lendings" table
+ALTER TABLE "public"."lendings" ADD COLUMN "lend_operation_id" uuid NULL, ADD COLUMN "return_operation_id" uuid NULL;
+-- Create index "lendings_lend_operation_id_key" to table: "lendings"
+CREATE UNIQUE INDEX "lendings_lend_operation_id_key" ON "public"."lendings" ("lend_operation_id");
+-- Create index "lendings_return_operation_id_key" to table: "lendings"
+CREATE UNIQUE INDEX "lendings_return_operation_id_key" ON "public"."lendings" ("return_operation_id");
@@ -0,0 +1,14 @@
+-- Create "idempotency_keys" table
+CREATE TABLE "public"."idempotency_keys" (
+  "user_id" bigint NOT NULL,
+  "key" character varying(255) NOT NULL,
+  "fingerprint" bytea NOT NULL,
+  "status" smallint NULL,
+  "content_type" character varying(255) NULL,
+  "body" bytea NULL,
+  "created_at" timestamptz NOT NULL,
+  PRIMARY KEY ("user_id", "key"),
+  CONSTRAINT "idempotency_keys_user_id_fkey" FOREIGN KEY ("user_id") REFERENCES "public"."users" ("id") ON UPDATE NO ACTION ON DELETE CASCADE
+);
+-- Create index "idempotency_keys_created_at_idx" to table: "idempotency_keys"
+CREATE INDEX "idempotency_keys_created_at_idx" ON "public"."idempotency_keys" ("created_at");
@@ -0,0 +1,15 @@
+-- Create "devices" table
+CREATE TABLE "public"."devices" (
+  "id" bigserial NOT NULL,
+  "library_id" bigint NOT NULL,
+  "name" character varying(50) NOT NULL,
+  "key_hash" character varying(255) NOT NULL,
+  "created_at" timestamptz NOT NULL,
+  "last_used_at" timestamptz NULL,
+  PRIMARY KEY ("id"),
+  CONSTRAINT "devices_library_id_fkey" FOREIGN KEY ("library_id") REFERENCES "public"."libraries" ("id") ON UPDATE NO ACTION ON DELETE CASCADE
+);
+-- Create index "devices_library_id_idx" to table: "devices"
+CREATE INDEX "devices_library_id_idx" ON "public"."devices" ("library_id");
+-- Modify "lendings" table
+ALTER TABLE "public"."lendings" ADD COLUMN "device_id" bigint NULL, ADD CONSTRAINT "lendings_device_id_fkey" FOREIGN KEY ("device_id") REFERENCES "public"."devices" ("id") ON UPDATE NO ACTION ON DELETE SET NULL;
@@ -0,0 +1,5 @@
+-- Modify "idempotency_keys" table
+ALTER TABLE "public"."idempotency_keys" DROP CONSTRAINT "idempotency_keys_pkey", DROP CONSTRAINT "idempotency_keys_user_id_fkey";
+ALTER TABLE "public"."idempotency_keys" RENAME COLUMN "user_id" TO "caller_id";
+ALTER TABLE "public"."idempotency_keys" ADD COLUMN "caller_kind" character varying(16) NOT NULL DEFAULT 'user', ADD CONSTRAINT "idempotency_keys_caller_kind_check" CHECK ((caller_kind)::text = ANY ((ARRAY['user'::character varying, 'device'::character varying])::text[])), ADD PRIMARY KEY ("caller_kind", "caller_id", "key");
+ALTER TABLE "public"."idempotency_keys" ALTER COLUMN "caller_kind" DROP DEFAULT;
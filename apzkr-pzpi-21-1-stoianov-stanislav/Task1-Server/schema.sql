@@ -3,7 +3,6 @@ create table users(
     name varchar(50) not null,
     email varchar(50) not null unique,
     password_hash text not null,
-    refresh_secret uuid not null unique,
     role varchar(32) not null
      check(role in ('administrator', 'client'))
 );
@@ -16,6 +15,9 @@ create table libraries(
     overdue_rate numeric(10, 5) not null,
     currency VARCHAR(3) not null,
       check(currency in ('UAH', 'USD', 'EUR')),
+    balance_limit numeric(10, 2),
+    renewal_period smallint not null default 14,
+    max_renewals smallint not null default 2,
     owner_id bigint not null
       references users(id)
       on delete cascade
@@ -27,20 +29,165 @@ create table books(
     name varchar(50) not null,
     genre varchar(50) not null,
     author varchar(50) not null,
+    isbn varchar(13),
+    search tsvector not null
+      generated always as (
+        setweight(to_tsvector('simple', name), 'A')
+          || setweight(to_tsvector('simple', author), 'B')
+          || setweight(to_tsvector('simple', genre), 'C')
+      ) stored,
     library_id bigint not null
       references libraries(id)
-      on delete cascade
+      on delete cascade,
+    unique(library_id, isbn)
 );
 
-create table lendings(
+create index books_isbn_idx
+  on books(isbn);
+
+create index books_search_idx
+  on books using gin(search);
+
+create table copies(
     id bigserial primary key,
     book_id bigint not null
       references books(id)
       on delete cascade,
+    barcode varchar(32) not null unique,
+    shelf_location varchar(50) not null,
+    condition varchar(32) not null
+      check(condition in ('new', 'good', 'fair', 'poor', 'damaged'))
+);
+
+create index copies_book_id_idx
+  on copies(book_id);
+
+create table devices(
+    id bigserial primary key,
+    library_id bigint not null
+      references libraries(id)
+      on delete cascade,
+    name varchar(50) not null,
+    key_hash varchar(255) not null,
+    created_at timestamptz not null,
+    last_used_at timestamptz
+);
+
+create index devices_library_id_idx
+  on devices(library_id);
+
+create table lendings(
+    id bigserial primary key,
+    copy_id bigint not null
+      references copies(id)
+      on delete cascade,
     lendee_id bigint not null
       references users(id)
       on delete cascade,
     lent_on date not null,
     due date not null,
-    returned_on date
+    returned_on date,
+    renewals smallint not null default 0,
+    lending_fee numeric(10, 2),
+    overdue_fee numeric(10, 2),
+    closed_by_migration boolean not null default false,
+    lend_operation_id uuid unique,
+    return_operation_id uuid unique,
+    device_id bigint
+      references devices(id)
+      on delete set null
+);
+
+create unique index lendings_active_copy_id_key
+  on lendings(copy_id)
+  where returned_on is null;
+
+create table lending_renewals(
+    id bigserial primary key,
+    lending_id bigint not null
+      references lendings(id)
+      on delete cascade,
+    renewed_on timestamptz not null,
+    previous_due date not null,
+    new_due date not null
 );
+
+create index lending_renewals_lending_id_idx
+  on lending_renewals(lending_id);
+
+create table holds(
+    id bigserial primary key,
+    book_id bigint not null
+      references books(id)
+      on delete cascade,
+    user_id bigint not null
+      references users(id)
+      on delete cascade,
+    status varchar(32) not null
+      check(status in
+        ('waiting', 'ready', 'fulfilled', 'cancelled', 'expired')),
+    placed_at timestamptz not null,
+    ready_until date
+);
+
+create unique index holds_active_book_id_user_id_key
+  on holds(book_id, user_id)
+  where status in ('waiting', 'ready');
+
+create table sessions(
+    id bigserial primary key,
+    user_id bigint not null
+      references users(id)
+      on delete cascade,
+    refresh_secret uuid not null unique,
+    device_label varchar(50) not null,
+    user_agent varchar(255) not null,
+    created_at timestamptz not null,
+    last_used_at timestamptz not null
+);
+
+create table rotated_refresh_secrets(
+    secret uuid primary key,
+    session_id bigint not null
+      references sessions(id)
+      on delete cascade
+);
+
+create table ledger_entries(
+    id bigserial primary key,
+    user_id bigint not null
+      references users(id)
+      on delete cascade,
+    library_id bigint not null
+      references libraries(id)
+      on delete cascade,
+    lending_id bigint
+      references lendings(id)
+      on delete set null,
+    kind varchar(32) not null
+      check(kind in
+        ('lending_charge', 'overdue_penalty', 'adjustment', 'payment')),
+    amount numeric(10, 2) not null,
+    currency varchar(3) not null
+      check(currency in ('UAH', 'USD', 'EUR')),
+    recorded_at timestamptz not null
+);
+
+create index ledger_entries_user_id_library_id_idx
+  on ledger_entries(user_id, library_id);
+
+create table idempotency_keys(
+    caller_kind varchar(16) not null
+      check(caller_kind in ('user', 'device')),
+    caller_id bigint not null,
+    key varchar(255) not null,
+    fingerprint bytea not null,
+    status smallint,
+    content_type varchar(255),
+    body bytea,
+    created_at timestamptz not null,
+    primary key (caller_kind, caller_id, key)
+);
+
+create index idempotency_keys_created_at_idx
+  on idempotency_keys(created_at);
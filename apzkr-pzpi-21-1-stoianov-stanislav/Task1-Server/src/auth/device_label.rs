@@ -0,0 +1,28 @@
+use serde::Serialize;
+
+use crate::Error;
+
+pub type UnvalidatedDeviceLabel = String;
+
+#[derive(Clone, Debug, Default, sqlx::Type, Serialize)]
+#[sqlx(transparent)]
+#[serde(transparent)]
+pub struct DeviceLabel(UnvalidatedDeviceLabel);
+
+impl DeviceLabel {
+    pub fn new(label: UnvalidatedDeviceLabel) -> crate::Result<Self> {
+        if label.len() > 50 {
+            Err(Error::Validation("device label is too long"))
+        } else {
+            Ok(Self(label))
+        }
+    }
+}
+
+impl TryFrom<UnvalidatedDeviceLabel> for DeviceLabel {
+    type Error = Error;
+
+    fn try_from(value: UnvalidatedDeviceLabel) -> Result<Self, Self::Error> {
+        Self::new(value)
+    }
+}
@@ -1,37 +1,54 @@
+mod device_label;
 mod email;
 mod name;
 mod password;
 mod role;
 mod token;
+mod user_agent;
 
+mod refresh;
+mod session;
 mod sign_in;
+mod sign_out;
 mod sign_up;
 mod user;
 
+pub use password::{hash_secret, verify_secret, PasswordHash};
+pub use refresh::refresh;
 pub use role::Role;
+pub use session::{list_sessions, revoke_session};
 pub use sign_in::sign_in;
+pub use sign_out::{sign_out, sign_out_all};
 pub use sign_up::sign_up;
 pub use token::parse_access_token;
 pub use user::{check_permission, get_all_users, get_user, update_user};
+pub use user_agent::UserAgent;
 
+pub use self::{email::Email, name::Name};
+
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 use crate::id::{tag, Id};
 
 use self::{
-    email::{Email, UnvalidatedEmail},
-    name::{Name, UnvalidatedName},
+    device_label::{DeviceLabel, UnvalidatedDeviceLabel},
+    email::UnvalidatedEmail,
+    name::UnvalidatedName,
     password::UnvalidatedPassword,
     token::{AccessToken, RefreshToken},
 };
 
 pub type UserId = Id<{ tag("user") }>;
 
+pub type SessionId = Id<{ tag("session") }>;
+
 #[derive(Clone, Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Credentials {
     pub email: UnvalidatedEmail,
     pub password: UnvalidatedPassword,
+    pub device_label: Option<UnvalidatedDeviceLabel>,
 }
 
 #[derive(Clone, Debug, Serialize)]
@@ -42,6 +59,23 @@ pub struct User {
     pub email: Email,
 }
 
+#[derive(Clone, Copy, Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum UserSort {
+    #[default]
+    Name,
+    Email,
+}
+
+impl UserSort {
+    pub fn column(self) -> &'static str {
+        match self {
+            Self::Name => "name",
+            Self::Email => "email",
+        }
+    }
+}
+
 #[derive(Clone, Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct UpdateUser {
@@ -53,3 +87,14 @@ pub struct TokenPair {
     pub access_token: AccessToken,
     pub refresh_token: RefreshToken,
 }
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Session {
+    pub id: SessionId,
+    pub device_label: DeviceLabel,
+    pub user_agent: UserAgent,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: DateTime<Utc>,
+    pub current: bool,
+}
@@ -49,10 +49,17 @@ fn validate_password(password: &str) -> Result<(), &'static str> {
 pub fn hash_password(
     password: &Password,
     config: HasherConfig,
+) -> crate::Result<PasswordHash> {
+    hash_secret(password.0.expose_secret().as_bytes(), config)
+}
+
+#[tracing::instrument(skip_all, err(Debug))]
+pub fn hash_secret(
+    secret: &[u8],
+    config: HasherConfig,
 ) -> crate::Result<PasswordHash> {
     let hasher = hasher(config.key.expose_secret().as_bytes(), config.params)?;
-    let password = password.0.expose_secret().as_bytes();
-    hash_bytes(hasher, password)
+    hash_bytes(hasher, secret)
 }
 
 #[tracing::instrument(skip_all, err(Debug, level = "debug"))]
@@ -60,19 +67,27 @@ pub fn verify_password(
     password: &UnvalidatedPassword,
     hash: Option<&PasswordHash>,
     config: HasherConfig,
+) -> crate::Result<()> {
+    verify_secret(password.expose_secret().as_bytes(), hash, config)
+}
+
+#[tracing::instrument(skip_all, err(Debug, level = "debug"))]
+pub fn verify_secret(
+    secret: &[u8],
+    hash: Option<&PasswordHash>,
+    config: HasherConfig,
 ) -> crate::Result<()> {
     let hasher = hasher(config.key.expose_secret().as_bytes(), config.params)?;
-    let password = password.expose_secret().as_bytes();
     match hash {
         Some(hash) => {
             let hash = argon2::PasswordHash::new(&hash.0)
                 .context("parse password hash")?;
             hasher
-                .verify_password(password, &hash)
+                .verify_password(secret, &hash)
                 .map_err(|_| Error::InvalidCredentials)
         }
         None => {
-            hash_bytes(hasher, password).ok();
+            hash_bytes(hasher, secret).ok();
             Err(Error::InvalidCredentials)
         }
     }
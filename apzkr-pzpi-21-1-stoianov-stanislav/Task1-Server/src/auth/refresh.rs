@@ -0,0 +1,89 @@
+use crate::{database::Database, state::AppState, telemetry, Error};
+
+use super::{
+    token::{
+        create_access_token, create_refresh_token, parse_refresh_token,
+        RefreshSecret,
+    },
+    TokenPair, UserId,
+};
+
+#[tracing::instrument(skip_all)]
+pub async fn refresh(
+    refresh_token: &str,
+    state: AppState,
+) -> crate::Result<TokenPair> {
+    let old_secret = parse_refresh_token(refresh_token, &state.jwt_config)
+        .map_err(|_| Error::LoggedOff)
+        .inspect_err(telemetry::debug)?;
+    let new_secret = RefreshSecret::new();
+    let Some(id) =
+        rotate_secret(&old_secret, &new_secret, &state.database).await?
+    else {
+        if let Some(id) = revoke_family(&old_secret, &state.database).await? {
+            tracing::warn!("refresh token reuse detected for session {id}");
+        }
+        return Err(Error::LoggedOff).inspect_err(telemetry::debug);
+    };
+    let id = UserId::new(id, &state.id_cipher);
+    let access_token = create_access_token(id, &state.jwt_config)?;
+    let refresh_token = create_refresh_token(new_secret, &state.jwt_config)?;
+    Ok(TokenPair {
+        access_token,
+        refresh_token,
+    })
+}
+
+#[tracing::instrument(skip(db), err(Debug))]
+async fn rotate_secret(
+    old_secret: &RefreshSecret,
+    new_secret: &RefreshSecret,
+    db: &Database,
+) -> crate::Result<Option<i64>> {
+    sqlx::query_as::<_, (_,)>(
+        "
+        with rotated as (
+          update sessions
+          set (refresh_secret, last_used_at) = ($1, now())
+          where refresh_secret = $2
+          returning id, user_id
+        ), history as (
+          insert into rotated_refresh_secrets
+            (secret, session_id)
+          select $2, id
+          from rotated
+        )
+        select user_id
+        from rotated;
+        ",
+    )
+    .bind(new_secret)
+    .bind(old_secret)
+    .fetch_optional(db)
+    .await
+    .map(|maybe| maybe.map(|id| id.0))
+    .map_err(Error::from)
+}
+
+#[tracing::instrument(skip(db), err(Debug))]
+async fn revoke_family(
+    rotated_secret: &RefreshSecret,
+    db: &Database,
+) -> crate::Result<Option<i64>> {
+    sqlx::query_as::<_, (_,)>(
+        "
+        delete from sessions
+        where id = (
+          select session_id
+          from rotated_refresh_secrets
+          where secret = $1
+        )
+        returning id;
+        ",
+    )
+    .bind(rotated_secret)
+    .fetch_optional(db)
+    .await
+    .map(|maybe| maybe.map(|id| id.0))
+    .map_err(Error::from)
+}
@@ -0,0 +1,116 @@
+use chrono::{DateTime, Utc};
+
+use crate::{database::Database, state::AppState, telemetry, Error};
+
+use super::{
+    device_label::DeviceLabel,
+    token::{parse_refresh_token, RefreshSecret},
+    user_agent::UserAgent,
+    Session, SessionId, UserId,
+};
+
+#[tracing::instrument(skip(refresh_token, state))]
+pub async fn list_sessions(
+    user_id: UserId,
+    refresh_token: Option<&str>,
+    state: AppState,
+) -> crate::Result<Vec<Session>> {
+    let user_id = user_id
+        .sql_id(&state.id_cipher)
+        .map_err(|_| Error::LoggedOff)
+        .inspect_err(telemetry::debug)?;
+    let current_secret = refresh_token
+        .and_then(|token| parse_refresh_token(token, &state.jwt_config).ok());
+    get_user_sessions(user_id, &state.database)
+        .await
+        .map(|sessions| {
+            sessions
+                .into_iter()
+                .map(|session| Session {
+                    id: SessionId::new(session.id, &state.id_cipher),
+                    current: current_secret.as_ref().is_some_and(|secret| {
+                        *secret == session.refresh_secret
+                    }),
+                    device_label: session.device_label,
+                    user_agent: session.user_agent,
+                    created_at: session.created_at,
+                    last_used_at: session.last_used_at,
+                })
+                .collect()
+        })
+}
+
+#[tracing::instrument(skip(state))]
+pub async fn revoke_session(
+    user_id: UserId,
+    session_id: SessionId,
+    state: AppState,
+) -> crate::Result<()> {
+    let user_id = user_id
+        .sql_id(&state.id_cipher)
+        .map_err(|_| Error::LoggedOff)
+        .inspect_err(telemetry::debug)?;
+    let session_id = session_id
+        .sql_id(&state.id_cipher)
+        .map_err(|_| Error::NotFound)
+        .inspect_err(telemetry::debug)?;
+    delete_session(user_id, session_id, &state.database).await
+}
+
+#[derive(Clone, Debug, sqlx::FromRow)]
+struct DbSession {
+    id: i64,
+    refresh_secret: RefreshSecret,
+    device_label: DeviceLabel,
+    user_agent: UserAgent,
+    created_at: DateTime<Utc>,
+    last_used_at: DateTime<Utc>,
+}
+
+#[tracing::instrument(skip(db), err(Debug))]
+async fn get_user_sessions(
+    user_id: i64,
+    db: &Database,
+) -> crate::Result<Vec<DbSession>> {
+    sqlx::query_as(
+        "
+        select id, refresh_secret, device_label, user_agent,
+          created_at, last_used_at
+        from sessions
+        where user_id = $1
+        order by last_used_at desc;
+        ",
+    )
+    .bind(user_id)
+    .fetch_all(db)
+    .await
+    .map_err(Error::from)
+}
+
+#[tracing::instrument(skip(db))]
+async fn delete_session(
+    user_id: i64,
+    session_id: i64,
+    db: &Database,
+) -> crate::Result<()> {
+    match sqlx::query(
+        "
+        delete from sessions
+        where id = $1
+          and user_id = $2;
+        ",
+    )
+    .bind(session_id)
+    .bind(user_id)
+    .execute(db)
+    .await
+    .map_err(Error::from)
+    .inspect_err(telemetry::error)?
+    .rows_affected()
+    {
+        0 => Err(Error::NotFound),
+        1 => Ok(()),
+        _ => unreachable!(),
+    }
+    .inspect_err(telemetry::debug)
+}
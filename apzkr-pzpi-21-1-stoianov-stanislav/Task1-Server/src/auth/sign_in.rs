@@ -1,20 +1,28 @@
-use crate::{database::Database, state::AppState, telemetry};
+use crate::{database::Database, state::AppState, telemetry, Error};
 
 use super::{
+    device_label::DeviceLabel,
     email::UnvalidatedEmail,
     password::{verify_password, PasswordHash},
     token::{create_access_token, create_refresh_token, RefreshSecret},
+    user_agent::UserAgent,
     Credentials, TokenPair, UserId,
 };
 
 #[tracing::instrument(skip(state))]
 pub async fn sign_in(
     credentials: Credentials,
+    user_agent: UserAgent,
     state: AppState,
 ) -> crate::Result<TokenPair> {
-    let (user_data, hash) = get_user(&credentials.email, &state.database)
+    let device_label = credentials
+        .device_label
+        .map(DeviceLabel::new)
+        .transpose()?
+        .unwrap_or_default();
+    let (id, hash) = get_user(&credentials.email, &state.database)
         .await?
-        .map(|u| ((u.id, u.refresh_secret), u.password_hash))
+        .map(|u| (u.id, u.password_hash))
         .unzip();
     telemetry::instrument_blocking(move || {
         verify_password(
@@ -24,11 +32,18 @@ pub async fn sign_in(
         )
     })
     .await??;
-    let (id, refresh_secret) = user_data.unwrap();
+    let id = id.unwrap();
+    let session = NewSession {
+        user_id: id,
+        refresh_secret: RefreshSecret::new(),
+        device_label,
+        user_agent,
+    };
+    save_session(&session, &state.database).await?;
     let id = UserId::new(id, &state.id_cipher);
     let access_token = create_access_token(id, &state.jwt_config)?;
     let refresh_token =
-        create_refresh_token(refresh_secret, &state.jwt_config)?;
+        create_refresh_token(session.refresh_secret, &state.jwt_config)?;
     Ok(TokenPair {
         access_token,
         refresh_token,
@@ -39,7 +54,14 @@ pub async fn sign_in(
 struct DbUser {
     id: i64,
     password_hash: PasswordHash,
+}
+
+#[derive(Clone, Debug)]
+struct NewSession {
+    user_id: i64,
     refresh_secret: RefreshSecret,
+    device_label: DeviceLabel,
+    user_agent: UserAgent,
 }
 
 #[tracing::instrument(skip(db), err(Debug))]
@@ -49,7 +71,7 @@ async fn get_user(
 ) -> crate::Result<Option<DbUser>> {
     sqlx::query_as(
         "
-        select id, password_hash, refresh_secret
+        select id, password_hash
         from users
         where email = $1;
         ",
@@ -57,5 +79,29 @@ async fn get_user(
     .bind(email)
     .fetch_optional(db)
     .await
-    .map_err(crate::Error::from)
+    .map_err(Error::from)
+}
+
+#[tracing::instrument(skip(db), err(Debug))]
+async fn save_session(
+    session: &NewSession,
+    db: &Database,
+) -> crate::Result<()> {
+    sqlx::query(
+        "
+        insert into sessions
+          (user_id, refresh_secret, device_label, user_agent,
+           created_at, last_used_at)
+        values
+          ($1, $2, $3, $4, now(), now());
+        ",
+    )
+    .bind(session.user_id)
+    .bind(&session.refresh_secret)
+    .bind(&session.device_label)
+    .bind(&session.user_agent)
+    .execute(db)
+    .await
+    .map(|_| ())
+    .map_err(Error::from)
 }
@@ -0,0 +1,72 @@
+use crate::{database::Database, state::AppState, telemetry, Error};
+
+use super::{
+    token::{parse_refresh_token, RefreshSecret},
+    UserId,
+};
+
+#[tracing::instrument(skip_all)]
+pub async fn sign_out(
+    refresh_token: &str,
+    state: AppState,
+) -> crate::Result<()> {
+    let secret = parse_refresh_token(refresh_token, &state.jwt_config)
+        .map_err(|_| Error::LoggedOff)
+        .inspect_err(telemetry::debug)?;
+    delete_session(&secret, &state.database).await
+}
+
+#[tracing::instrument(skip(state))]
+pub async fn sign_out_all(
+    user_id: UserId,
+    state: AppState,
+) -> crate::Result<()> {
+    let user_id = user_id
+        .sql_id(&state.id_cipher)
+        .map_err(|_| Error::LoggedOff)
+        .inspect_err(telemetry::debug)?;
+    delete_user_sessions(user_id, &state.database).await
+}
+
+#[tracing::instrument(skip(db))]
+async fn delete_session(
+    secret: &RefreshSecret,
+    db: &Database,
+) -> crate::Result<()> {
+    match sqlx::query(
+        "
+        delete from sessions
+        where refresh_secret = $1;
+        ",
+    )
+    .bind(secret)
+    .execute(db)
+    .await
+    .map_err(Error::from)
+    .inspect_err(telemetry::error)?
+    .rows_affected()
+    {
+        0 => Err(Error::LoggedOff),
+        1 => Ok(()),
+        _ => unreachable!(),
+    }
+    .inspect_err(telemetry::debug)
+}
+
+#[tracing::instrument(skip(db), err(Debug))]
+async fn delete_user_sessions(
+    user_id: i64,
+    db: &Database,
+) -> crate::Result<()> {
+    sqlx::query(
+        "
+        delete from sessions
+        where user_id = $1;
+        ",
+    )
+    .bind(user_id)
+    .execute(db)
+    .await
+    .map(|_| ())
+    .map_err(Error::from)
+}
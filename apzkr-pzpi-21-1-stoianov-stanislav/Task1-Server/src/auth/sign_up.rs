@@ -12,7 +12,6 @@ use super::{
     name::Name,
     password::{hash_password, Password, PasswordHash},
     role::Role,
-    token::RefreshSecret,
     Credentials,
 };
 
@@ -21,7 +20,6 @@ struct NewUser {
     name: Name,
     email: Email,
     password_hash: PasswordHash,
-    refresh_secret: RefreshSecret,
     role: Role,
 }
 
@@ -36,12 +34,10 @@ pub async fn sign_up(
         hash_password(&password, (*state.hasher_config).clone())
     })
     .await??;
-    let refresh_secret = RefreshSecret::new();
     let user = NewUser {
         name: Name::default(),
         email,
         password_hash,
-        refresh_secret,
         role: Role::Client,
     };
     save_user(&user, &state.database).await
@@ -52,15 +48,14 @@ async fn save_user(user: &NewUser, db: &Database) -> crate::Result<()> {
     match sqlx::query(
         "
         insert into users
-          (name, email, password_hash, refresh_secret, role)
+          (name, email, password_hash, role)
         values
-          ($1, $2, $3, $4, $5);
+          ($1, $2, $3, $4);
         ",
     )
     .bind(&user.name)
     .bind(&user.email)
     .bind(&user.password_hash)
-    .bind(&user.refresh_secret)
     .bind(user.role)
     .execute(db)
     .await
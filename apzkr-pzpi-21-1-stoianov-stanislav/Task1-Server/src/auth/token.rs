@@ -16,7 +16,7 @@ pub type AccessToken = String;
 
 pub type RefreshToken = String;
 
-#[derive(Clone, sqlx::Type, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Eq, sqlx::Type, Serialize, Deserialize)]
 #[sqlx(transparent)]
 #[serde(transparent)]
 pub struct RefreshSecret(Uuid);
@@ -77,7 +77,6 @@ pub fn parse_access_token(
     parse_claims::<AccessClaims>(token, config).map(|claims| claims.id)
 }
 
-#[allow(unused)]
 pub fn parse_refresh_token(
     token: &str,
     config: &JwtConfig,
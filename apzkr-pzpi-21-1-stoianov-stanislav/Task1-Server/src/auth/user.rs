@@ -1,6 +1,13 @@
-use crate::{database::Database, state::AppState, telemetry, Error};
+use crate::{
+    database::Database,
+    page::{check_cursor, Page, PageRequest},
+    state::AppState,
+    telemetry, Error,
+};
 
-use super::{email::Email, name::Name, role::Role, UpdateUser, User, UserId};
+use super::{
+    email::Email, name::Name, role::Role, UpdateUser, User, UserId, UserSort,
+};
 
 #[derive(Clone, Debug, sqlx::FromRow)]
 struct UserInfo {
@@ -17,23 +24,30 @@ struct UpdateUserInfo {
 #[tracing::instrument(skip(state))]
 pub async fn get_all_users(
     admin_id: UserId,
+    page: PageRequest<UserId, UserSort>,
     state: AppState,
-) -> crate::Result<Vec<User>> {
+) -> crate::Result<Page<User, UserId>> {
     check_permission(admin_id, &state, |role| {
         matches!(role, Role::Administrator)
     })
     .await
     .inspect_err(telemetry::debug)?;
-    get_all_user_info(&state.database).await.map(|users| {
-        users
-            .into_iter()
-            .map(|user_info| User {
-                id: UserId::new(user_info.id, &state.id_cipher),
-                name: user_info.name,
-                email: user_info.email,
-            })
-            .collect()
-    })
+    let cursor = page.sql_cursor(&state.id_cipher)?;
+    check_cursor("users", cursor, &state.database).await?;
+    let limit = page.limit();
+    get_users_page(cursor, limit, page.sort, &state.database)
+        .await
+        .map(|users| {
+            let users = users
+                .into_iter()
+                .map(|user_info| User {
+                    id: UserId::new(user_info.id, &state.id_cipher),
+                    name: user_info.name,
+                    email: user_info.email,
+                })
+                .collect();
+            Page::new(users, limit, |user| user.id)
+        })
 }
 
 #[tracing::instrument(skip(state))]
@@ -94,13 +108,25 @@ struct AllUser {
 }
 
 #[tracing::instrument(skip(db), err(Debug))]
-async fn get_all_user_info(db: &Database) -> crate::Result<Vec<AllUser>> {
-    sqlx::query_as(
+async fn get_users_page(
+    cursor: Option<i64>,
+    limit: i64,
+    sort: UserSort,
+    db: &Database,
+) -> crate::Result<Vec<AllUser>> {
+    sqlx::query_as(&format!(
         "
-        select id, name, email 
-        from users;
+        select id, name, email
+        from users
+        where $1::bigint is null
+          or ({key}, id) > (select {key}, id from users where id = $1)
+        order by {key}, id
+        limit $2 + 1;
         ",
-    )
+        key = sort.column(),
+    ))
+    .bind(cursor)
+    .bind(limit)
     .fetch_all(db)
     .await
     .map_err(Error::from)
@@ -0,0 +1,14 @@
+use serde::Serialize;
+
+const MAX_LENGTH: usize = 255;
+
+#[derive(Clone, Debug, Default, sqlx::Type, Serialize)]
+#[sqlx(transparent)]
+#[serde(transparent)]
+pub struct UserAgent(String);
+
+impl UserAgent {
+    pub fn new(user_agent: &str) -> Self {
+        Self(user_agent.chars().take(MAX_LENGTH).collect())
+    }
+}
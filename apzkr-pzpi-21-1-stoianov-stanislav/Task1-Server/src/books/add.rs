@@ -1,10 +1,17 @@
+use sqlx::error::ErrorKind;
+
 use crate::{
-    auth::UserId, database::Database, libraries::LibraryId, state::AppState,
+    auth::UserId,
+    copies::insert_default_copy,
+    database::{error_kind, Transaction},
+    libraries::LibraryId,
+    state::AppState,
     telemetry, Error,
 };
 
 use super::{
-    author::Author, check_owns, genre::Genre, name::Name, year::Year, NewBook,
+    author::Author, check_owns, genre::Genre, isbn::Isbn, name::Name,
+    year::Year, NewBook,
 };
 
 #[tracing::instrument(skip(state))]
@@ -23,42 +30,61 @@ pub async fn add_book(
         .map_err(|_| Error::NotFound)
         .inspect_err(telemetry::debug)?;
     check_owns(owner_id, library_id, &state.database).await?;
-    let book = DbBook {
-        library_id,
-        year: Year::new(book.year)?,
-        name: Name::new(book.name)?,
-        genre: Genre::new(book.genre)?,
-        author: Author::new(book.author)?,
-    };
-    insert_book(book, &state.database).await
+    let book = DbBook::new(library_id, book)?;
+    let mut tx = state.database.begin().await?;
+    let book_id = insert_book(book, &mut tx).await?;
+    insert_default_copy(book_id, &mut tx).await?;
+    tx.commit().await.map_err(Error::from)
 }
 
 #[derive(Clone, Debug)]
-struct DbBook {
+pub struct DbBook {
     library_id: i64,
     year: Year,
     name: Name,
     genre: Genre,
     author: Author,
+    isbn: Option<Isbn>,
+}
+
+impl DbBook {
+    pub fn new(library_id: i64, book: NewBook) -> crate::Result<Self> {
+        Ok(Self {
+            library_id,
+            year: Year::new(book.year)?,
+            name: Name::new(book.name)?,
+            genre: Genre::new(book.genre)?,
+            author: Author::new(book.author)?,
+            isbn: book.isbn.map(Isbn::new).transpose()?,
+        })
+    }
 }
 
-#[tracing::instrument(skip(db), err(Debug))]
-async fn insert_book(book: DbBook, db: &Database) -> crate::Result<()> {
-    sqlx::query(
+#[tracing::instrument(skip(tx), err(Debug))]
+pub async fn insert_book(
+    book: DbBook,
+    tx: &mut Transaction<'_>,
+) -> crate::Result<i64> {
+    sqlx::query_as::<_, (_,)>(
         "
         insert into books
-          (year, name, genre, author, library_id)
+          (year, name, genre, author, isbn, library_id)
         values
-          ($1, $2, $3, $4, $5);
+          ($1, $2, $3, $4, $5, $6)
+        returning id;
         ",
     )
     .bind(&book.year)
     .bind(&book.name)
     .bind(&book.genre)
     .bind(&book.author)
+    .bind(&book.isbn)
     .bind(book.library_id)
-    .execute(db)
+    .fetch_one(&mut **tx)
     .await
-    .map(|_| ())
-    .map_err(Error::from)
+    .map(|id| id.0)
+    .map_err(|e| match error_kind(&e) {
+        Some(ErrorKind::UniqueViolation) => Error::IsbnTaken,
+        _ => Error::from(e),
+    })
 }
@@ -0,0 +1,86 @@
+use futures_util::stream::BoxStream;
+
+use crate::{
+    auth::UserId,
+    database::Database,
+    export::{export, Encoder, Export, ExportFormat, Serialized},
+    libraries::LibraryId,
+    state::AppState,
+    telemetry, Error,
+};
+
+use super::{
+    author::Author, check_owns, genre::Genre, isbn::Isbn, marc::MarcWriter,
+    name::Name, year::Year, BookFormat, BookId, ExportedBook,
+};
+
+#[tracing::instrument(skip(state))]
+pub async fn export_books(
+    owner_id: UserId,
+    library_id: LibraryId,
+    format: BookFormat,
+    state: AppState,
+) -> crate::Result<Export> {
+    let owner_id = owner_id
+        .sql_id(&state.id_cipher)
+        .map_err(|_| Error::LoggedOff)
+        .inspect_err(telemetry::debug)?;
+    let library_id = library_id
+        .sql_id(&state.id_cipher)
+        .map_err(|_| Error::NotFound)
+        .inspect_err(telemetry::debug)?;
+    check_owns(owner_id, library_id, &state.database).await?;
+    let encoder: Box<dyn Encoder<ExportedBook>> = match format {
+        BookFormat::Csv => Box::new(Serialized::new(ExportFormat::Csv)),
+        BookFormat::Jsonl => Box::new(Serialized::new(ExportFormat::Jsonl)),
+        BookFormat::Marc => Box::new(MarcWriter::Binary),
+        BookFormat::Marcxml => Box::new(MarcWriter::Xml),
+    };
+    let cipher = state.id_cipher.clone();
+    Ok(export(
+        format.content_type(),
+        format!("books.{}", format.extension()),
+        state.database.clone(),
+        move |db| get_books(library_id, db),
+        move |book: DbBook| ExportedBook {
+            id: BookId::new(book.id, &cipher),
+            year: book.year,
+            name: book.name,
+            genre: book.genre,
+            author: book.author,
+            isbn: book.isbn,
+            copies: book.copies,
+        },
+        encoder,
+    ))
+}
+
+#[derive(Clone, Debug, sqlx::FromRow)]
+struct DbBook {
+    id: i64,
+    year: Year,
+    name: Name,
+    genre: Genre,
+    author: Author,
+    isbn: Option<Isbn>,
+    copies: i64,
+}
+
+fn get_books(
+    library_id: i64,
+    db: &Database,
+) -> BoxStream<'_, sqlx::Result<DbBook>> {
+    sqlx::query_as(
+        "
+        select b.id, b.year, b.name, b.genre, b.author, b.isbn,
+          count(c.id) as copies
+        from books b
+        left join copies c on c.book_id = b.id
+        where b.library_id = $1
+        group by b.id
+        order by b.id;
+        ",
+    )
+    .bind(library_id)
+    .fetch(db)
+}
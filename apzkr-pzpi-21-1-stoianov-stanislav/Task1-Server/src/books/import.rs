@@ -0,0 +1,138 @@
+use crate::{
+    auth::UserId, copies::insert_default_copy, database::Transaction,
+    libraries::LibraryId, state::AppState, telemetry, Error,
+};
+
+use super::{
+    add::{insert_book, DbBook},
+    check_owns, marc, BookFormat, ImportOptions, ImportReport, NewBook,
+    RowError,
+};
+
+#[tracing::instrument(skip(data, state))]
+pub async fn import_books(
+    owner_id: UserId,
+    library_id: LibraryId,
+    options: ImportOptions,
+    data: Vec<u8>,
+    state: AppState,
+) -> crate::Result<ImportReport> {
+    let owner_id = owner_id
+        .sql_id(&state.id_cipher)
+        .map_err(|_| Error::LoggedOff)
+        .inspect_err(telemetry::debug)?;
+    let library_id = library_id
+        .sql_id(&state.id_cipher)
+        .map_err(|_| Error::NotFound)
+        .inspect_err(telemetry::debug)?;
+    check_owns(owner_id, library_id, &state.database).await?;
+    let rows = match options.format {
+        BookFormat::Csv => read_csv(&data)?,
+        BookFormat::Jsonl => read_jsonl(&data),
+        BookFormat::Marc => numbered(marc::read_binary(&data)),
+        BookFormat::Marcxml => numbered(marc::read_xml(&data)?),
+    };
+    let mut books = Vec::new();
+    let mut errors = Vec::new();
+    for (row, book) in rows {
+        let book = book.and_then(|book| {
+            DbBook::new(library_id, book).map_err(|e| e.to_string())
+        });
+        match book {
+            Ok(book) => books.push(ParsedBook { row, book }),
+            Err(error) => errors.push(RowError { row, error }),
+        }
+    }
+    if !errors.is_empty() {
+        return Ok(ImportReport::failed(options.dry_run, errors));
+    }
+    let mut tx = state.database.begin().await?;
+    for ParsedBook { row, book } in &books {
+        match insert_with_copy(book.clone(), &mut tx).await {
+            Ok(()) => {}
+            Err(error @ Error::IsbnTaken) => {
+                let error = RowError {
+                    row: *row,
+                    error: error.to_string(),
+                };
+                return Ok(ImportReport::failed(options.dry_run, vec![error]));
+            }
+            Err(error) => return Err(error),
+        }
+    }
+    if options.dry_run {
+        tx.rollback().await?;
+    } else {
+        tx.commit().await?;
+    }
+    Ok(ImportReport {
+        dry_run: options.dry_run,
+        imported: books.len(),
+        errors,
+    })
+}
+
+type Rows = Vec<(u64, Result<NewBook, String>)>;
+
+#[derive(Clone, Debug)]
+struct ParsedBook {
+    row: u64,
+    book: DbBook,
+}
+
+fn numbered(books: Vec<Result<NewBook, String>>) -> Rows {
+    (1..).zip(books).collect()
+}
+
+fn read_csv(data: &[u8]) -> crate::Result<Rows> {
+    let mut reader = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .from_reader(data);
+    let headers = reader
+        .headers()
+        .map_err(|_| Error::Validation("csv header is invalid"))
+        .inspect_err(telemetry::debug)?
+        .clone();
+    Ok(reader
+        .records()
+        .map(|record| match record {
+            Ok(record) => {
+                let line = record.position().map_or(0, |p| p.line());
+                let book = record
+                    .deserialize::<NewBook>(Some(&headers))
+                    .map_err(|e| match e.kind() {
+                        csv::ErrorKind::Deserialize { err, .. } => {
+                            err.to_string()
+                        }
+                        _ => e.to_string(),
+                    });
+                (line, book)
+            }
+            Err(error) => {
+                let line = error.position().map_or(0, |p| p.line());
+                (line, Err(error.to_string()))
+            }
+        })
+        .collect())
+}
+
+fn read_jsonl(data: &[u8]) -> Rows {
+    (1..)
+        .zip(data.split(|b| *b == b'\n'))
+        .filter(|(_, line)| !line.trim_ascii().is_empty())
+        .map(|(line, book)| {
+            (
+                line,
+                serde_json::from_slice(book).map_err(|e| e.to_string()),
+            )
+        })
+        .collect()
+}
+
+async fn insert_with_copy(
+    book: DbBook,
+    tx: &mut Transaction<'_>,
+) -> crate::Result<()> {
+    let book_id = insert_book(book, tx).await?;
+    insert_default_copy(book_id, tx).await
+}
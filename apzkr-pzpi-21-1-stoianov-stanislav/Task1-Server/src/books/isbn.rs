@@ -0,0 +1,83 @@
+use serde::Serialize;
+
+use crate::Error;
+
+pub type UnvalidatedIsbn = String;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, sqlx::Type, Serialize)]
+#[sqlx(transparent)]
+#[serde(transparent)]
+pub struct Isbn(UnvalidatedIsbn);
+
+impl Isbn {
+    pub fn new(isbn: UnvalidatedIsbn) -> crate::Result<Self> {
+        let isbn: String = isbn
+            .chars()
+            .filter(|c| !matches!(c, '-' | ' '))
+            .map(|c| c.to_ascii_uppercase())
+            .collect();
+        match isbn.len() {
+            10 => Self::from_isbn10(&isbn),
+            13 => Self::from_isbn13(isbn),
+            _ => Err(Error::Validation("isbn must have 10 or 13 digits")),
+        }
+    }
+
+    fn from_isbn10(isbn: &str) -> crate::Result<Self> {
+        let digits = isbn
+            .chars()
+            .enumerate()
+            .map(|(i, c)| match c {
+                'X' if i == 9 => Some(10),
+                c => c.to_digit(10),
+            })
+            .collect::<Option<Vec<_>>>()
+            .ok_or(Error::Validation("isbn contains invalid characters"))?;
+        let sum: u32 =
+            digits.iter().zip((1..=10).rev()).map(|(d, w)| d * w).sum();
+        if !sum.is_multiple_of(11) {
+            return Err(Error::Validation("isbn check digit is wrong"));
+        }
+        let mut isbn13 = format!("978{}", &isbn[..9]);
+        let check = check_digit13(&isbn13);
+        isbn13.push(char::from_digit(check, 10).unwrap());
+        Ok(Self(isbn13))
+    }
+
+    fn from_isbn13(isbn: String) -> crate::Result<Self> {
+        if !isbn.chars().all(|c| c.is_ascii_digit()) {
+            return Err(Error::Validation("isbn contains invalid characters"));
+        }
+        if !(isbn.starts_with("978") || isbn.starts_with("979")) {
+            return Err(Error::Validation("isbn must start with 978 or 979"));
+        }
+        if check_digit13(&isbn[..12]) != isbn[12..].parse::<u32>().unwrap() {
+            return Err(Error::Validation("isbn check digit is wrong"));
+        }
+        Ok(Self(isbn))
+    }
+}
+
+fn check_digit13(digits: &str) -> u32 {
+    let sum: u32 = digits
+        .chars()
+        .filter_map(|c| c.to_digit(10))
+        .zip([1, 3].into_iter().cycle())
+        .map(|(d, w)| d * w)
+        .sum();
+    (10 - sum % 10) % 10
+}
+
+impl TryFrom<UnvalidatedIsbn> for Isbn {
+    type Error = Error;
+
+    fn try_from(value: UnvalidatedIsbn) -> Result<Self, Self::Error> {
+        Self::new(value)
+    }
+}
+
+impl From<Isbn> for UnvalidatedIsbn {
+    fn from(value: Isbn) -> Self {
+        value.0
+    }
+}
@@ -0,0 +1,61 @@
+use crate::{
+    database::Database,
+    libraries::{self, LibraryId},
+    state::AppState,
+    Error,
+};
+
+use super::{
+    isbn::{Isbn, UnvalidatedIsbn},
+    BookId, BookLocation,
+};
+
+#[tracing::instrument(skip(state))]
+pub async fn find_by_isbn(
+    isbn: UnvalidatedIsbn,
+    state: AppState,
+) -> crate::Result<Vec<BookLocation>> {
+    let isbn = Isbn::new(isbn)?;
+    get_books_by_isbn(&isbn, &state.database)
+        .await
+        .map(|books| {
+            books
+                .into_iter()
+                .map(|book| BookLocation {
+                    id: BookId::new(book.id, &state.id_cipher),
+                    library_id: LibraryId::new(
+                        book.library_id,
+                        &state.id_cipher,
+                    ),
+                    library_name: book.library_name,
+                })
+                .collect()
+        })
+}
+
+#[derive(Clone, Debug, sqlx::FromRow)]
+struct DbBookLocation {
+    id: i64,
+    library_id: i64,
+    library_name: libraries::Name,
+}
+
+#[tracing::instrument(skip(db), err(Debug))]
+async fn get_books_by_isbn(
+    isbn: &Isbn,
+    db: &Database,
+) -> crate::Result<Vec<DbBookLocation>> {
+    sqlx::query_as(
+        "
+        select b.id, lib.id as library_id, lib.name as library_name
+        from books b
+        join libraries lib on lib.id = b.library_id
+        where b.isbn = $1
+        order by lib.name, lib.id;
+        ",
+    )
+    .bind(isbn)
+    .fetch_all(db)
+    .await
+    .map_err(Error::from)
+}
@@ -0,0 +1,369 @@
+use quick_xml::{
+    escape::escape,
+    events::{BytesStart, Event},
+    Reader,
+};
+
+use crate::{export::Encoder, Error};
+
+use super::{year::UnvalidatedYear, ExportedBook, NewBook};
+
+const SUBFIELD_DELIMITER: u8 = 0x1f;
+const FIELD_TERMINATOR: u8 = 0x1e;
+const RECORD_TERMINATOR: u8 = 0x1d;
+const LEADER_LEN: usize = 24;
+const DIRECTORY_ENTRY_LEN: usize = 12;
+const MARCXML_NAMESPACE: &str = "http://www.loc.gov/MARC21/slim";
+
+#[derive(Clone, Debug, Default)]
+struct Record {
+    control_fields: Vec<(String, String)>,
+    data_fields: Vec<DataField>,
+}
+
+#[derive(Clone, Debug)]
+struct DataField {
+    tag: String,
+    indicators: [char; 2],
+    subfields: Vec<(char, String)>,
+}
+
+impl Record {
+    fn from_book(book: &ExportedBook) -> Self {
+        let mut record = Self::default();
+        record
+            .control_fields
+            .push(("001".into(), book.id.to_string()));
+        if let Some(isbn) = book.isbn.clone() {
+            record.push("020", [' ', ' '], 'a', isbn.into());
+        }
+        record.push("100", ['1', ' '], 'a', book.author.clone().into());
+        record.push("245", ['1', '0'], 'a', book.name.clone().into());
+        let year = UnvalidatedYear::from(book.year.clone());
+        record.push("264", [' ', '1'], 'c', year.to_string());
+        record.push("650", [' ', '4'], 'a', book.genre.clone().into());
+        record
+    }
+
+    fn push(
+        &mut self,
+        tag: &str,
+        indicators: [char; 2],
+        code: char,
+        value: String,
+    ) {
+        self.data_fields.push(DataField {
+            tag: tag.into(),
+            indicators,
+            subfields: vec![(code, value)],
+        });
+    }
+
+    fn subfield(&self, tags: &[&str], code: char) -> Option<&str> {
+        tags.iter().find_map(|tag| {
+            self.data_fields
+                .iter()
+                .filter(|field| field.tag == *tag)
+                .flat_map(|field| &field.subfields)
+                .find(|(c, value)| *c == code && !value.trim().is_empty())
+                .map(|(_, value)| value.as_str())
+        })
+    }
+
+    fn into_book(self) -> Result<NewBook, String> {
+        let name = self
+            .subfield(&["245"], 'a')
+            .ok_or("record has no title (245$a)")?;
+        let author = self
+            .subfield(&["100"], 'a')
+            .ok_or("record has no author (100$a)")?;
+        let genre = self
+            .subfield(&["650"], 'a')
+            .ok_or("record has no subject (650$a)")?;
+        let year = self
+            .subfield(&["264", "260"], 'c')
+            .ok_or("record has no publication year (264$c)")?;
+        let year = parse_year(year)
+            .ok_or_else(|| format!("publication year {year:?} is invalid"))?;
+        let isbn = self
+            .subfield(&["020"], 'a')
+            .and_then(|isbn| isbn.split_whitespace().next());
+        Ok(NewBook {
+            year,
+            name: trim_punctuation(name),
+            genre: trim_punctuation(genre),
+            author: trim_punctuation(author),
+            isbn: isbn.map(str::to_owned),
+        })
+    }
+
+    fn to_binary(&self) -> Vec<u8> {
+        let mut directory = Vec::new();
+        let mut data = Vec::new();
+        let fields = self
+            .control_fields
+            .iter()
+            .map(|(tag, value)| (tag, value.as_bytes().to_vec()))
+            .chain(self.data_fields.iter().map(|field| {
+                let mut bytes = Vec::new();
+                for indicator in field.indicators {
+                    bytes.push(indicator as u8);
+                }
+                for (code, value) in &field.subfields {
+                    bytes.push(SUBFIELD_DELIMITER);
+                    bytes.push(*code as u8);
+                    bytes.extend_from_slice(value.as_bytes());
+                }
+                (&field.tag, bytes)
+            }));
+        for (tag, mut bytes) in fields {
+            bytes.push(FIELD_TERMINATOR);
+            directory.extend_from_slice(
+                format!("{tag}{:04}{:05}", bytes.len(), data.len()).as_bytes(),
+            );
+            data.extend_from_slice(&bytes);
+        }
+        directory.push(FIELD_TERMINATOR);
+        let base = LEADER_LEN + directory.len();
+        let len = base + data.len() + 1;
+        let mut record = leader(len, base).into_bytes();
+        record.extend_from_slice(&directory);
+        record.extend_from_slice(&data);
+        record.push(RECORD_TERMINATOR);
+        record
+    }
+
+    fn parse_binary(record: &[u8]) -> Option<Self> {
+        let leader = record.get(..LEADER_LEN)?;
+        let base: usize =
+            std::str::from_utf8(&leader[12..17]).ok()?.parse().ok()?;
+        let directory = record.get(LEADER_LEN..base.checked_sub(1)?)?;
+        let mut parsed = Self::default();
+        for entry in directory.chunks(DIRECTORY_ENTRY_LEN) {
+            let entry = std::str::from_utf8(entry).ok()?;
+            let tag = entry.get(..3)?;
+            let len: usize = entry.get(3..7)?.parse().ok()?;
+            let start: usize = entry.get(7..12)?.parse().ok()?;
+            let field = record.get(base + start..base + start + len)?;
+            let field =
+                field.strip_suffix(&[FIELD_TERMINATOR]).unwrap_or(field);
+            if tag < "010" {
+                let value = String::from_utf8(field.to_vec()).ok()?;
+                parsed.control_fields.push((tag.into(), value));
+                continue;
+            }
+            let mut parts = field.split(|b| *b == SUBFIELD_DELIMITER);
+            let indicators = parts.next()?;
+            let subfields = parts
+                .filter_map(|part| part.split_first())
+                .map(|(code, value)| {
+                    String::from_utf8(value.to_vec())
+                        .ok()
+                        .map(|value| (*code as char, value))
+                })
+                .collect::<Option<Vec<_>>>()?;
+            parsed.data_fields.push(DataField {
+                tag: tag.into(),
+                indicators: [
+                    indicators.first().map_or(' ', |b| *b as char),
+                    indicators.get(1).map_or(' ', |b| *b as char),
+                ],
+                subfields,
+            });
+        }
+        Some(parsed)
+    }
+
+    fn to_xml(&self) -> String {
+        let len = self.to_binary().len();
+        let base = LEADER_LEN
+            + DIRECTORY_ENTRY_LEN
+                * (self.control_fields.len() + self.data_fields.len())
+            + 1;
+        let mut xml = String::from("<record>");
+        xml.push_str(&format!("<leader>{}</leader>", leader(len, base)));
+        for (tag, value) in &self.control_fields {
+            xml.push_str(&format!(
+                "<controlfield tag=\"{}\">{}</controlfield>",
+                escape(tag),
+                escape(value)
+            ));
+        }
+        for field in &self.data_fields {
+            xml.push_str(&format!(
+                "<datafield tag=\"{}\" ind1=\"{}\" ind2=\"{}\">",
+                escape(&field.tag),
+                field.indicators[0],
+                field.indicators[1]
+            ));
+            for (code, value) in &field.subfields {
+                xml.push_str(&format!(
+                    "<subfield code=\"{code}\">{}</subfield>",
+                    escape(value)
+                ));
+            }
+            xml.push_str("</datafield>");
+        }
+        xml.push_str("</record>\n");
+        xml
+    }
+}
+
+fn leader(len: usize, base: usize) -> String {
+    format!("{len:05}nam a22{base:05} i 4500")
+}
+
+fn parse_year(value: &str) -> Option<UnvalidatedYear> {
+    value
+        .split(|c: char| !c.is_ascii_digit())
+        .find(|digits| digits.len() == 4)
+        .and_then(|digits| digits.parse().ok())
+}
+
+fn trim_punctuation(value: &str) -> String {
+    value
+        .trim()
+        .trim_end_matches([' ', '/', ':', ';', ',', '.'])
+        .to_owned()
+}
+
+pub fn read_binary(data: &[u8]) -> Vec<Result<NewBook, String>> {
+    data.split(|b| *b == RECORD_TERMINATOR)
+        .filter(|record| !record.trim_ascii().is_empty())
+        .map(|record| {
+            Record::parse_binary(record.trim_ascii_start())
+                .ok_or_else(|| "record is malformed".to_owned())
+                .and_then(Record::into_book)
+        })
+        .collect()
+}
+
+pub fn read_xml(data: &[u8]) -> crate::Result<Vec<Result<NewBook, String>>> {
+    parse_xml(data)
+        .map(|records| records.into_iter().map(Record::into_book).collect())
+        .map_err(|_| Error::Validation("marcxml is malformed"))
+}
+
+fn parse_xml(data: &[u8]) -> Result<Vec<Record>, quick_xml::Error> {
+    let mut reader = Reader::from_reader(data);
+    let mut buf = Vec::new();
+    let mut records = Vec::new();
+    let mut record = None::<Record>;
+    let mut control_tag = None;
+    let mut subfield_code = None;
+    let mut text = String::new();
+    loop {
+        match reader.read_event_into(&mut buf)? {
+            Event::Start(element) => {
+                text.clear();
+                match element.local_name().as_ref() {
+                    b"record" => record = Some(Record::default()),
+                    b"controlfield" => {
+                        control_tag = attribute(&element, b"tag")?;
+                    }
+                    b"datafield" => {
+                        let tag = attribute(&element, b"tag")?;
+                        let ind1 = attribute(&element, b"ind1")?;
+                        let ind2 = attribute(&element, b"ind2")?;
+                        if let (Some(record), Some(tag)) = (&mut record, tag) {
+                            record.data_fields.push(DataField {
+                                tag,
+                                indicators: [
+                                    first_char(ind1.as_deref()),
+                                    first_char(ind2.as_deref()),
+                                ],
+                                subfields: Vec::new(),
+                            });
+                        }
+                    }
+                    b"subfield" => {
+                        subfield_code = attribute(&element, b"code")?
+                            .and_then(|code| code.chars().next());
+                    }
+                    _ => {}
+                }
+            }
+            Event::Text(value) => text.push_str(&value.unescape()?),
+            Event::CData(value) => {
+                text.push_str(&String::from_utf8_lossy(&value.into_inner()))
+            }
+            Event::End(element) => match element.local_name().as_ref() {
+                b"record" => records.extend(record.take()),
+                b"controlfield" => {
+                    if let (Some(record), Some(tag)) =
+                        (&mut record, control_tag.take())
+                    {
+                        record.control_fields.push((tag, text.clone()));
+                    }
+                }
+                b"subfield" => {
+                    let field = record
+                        .as_mut()
+                        .and_then(|record| record.data_fields.last_mut());
+                    if let (Some(field), Some(code)) =
+                        (field, subfield_code.take())
+                    {
+                        field.subfields.push((code, text.clone()));
+                    }
+                }
+                _ => {}
+            },
+            Event::Eof if record.is_none() => break,
+            Event::Eof => {
+                return Err(quick_xml::Error::UnexpectedEof("record".into()))
+            }
+            _ => {}
+        }
+        buf.clear();
+    }
+    Ok(records)
+}
+
+fn attribute(
+    element: &BytesStart,
+    name: &[u8],
+) -> Result<Option<String>, quick_xml::Error> {
+    element
+        .try_get_attribute(name)?
+        .map(|attribute| {
+            attribute.unescape_value().map(|value| value.into_owned())
+        })
+        .transpose()
+}
+
+fn first_char(value: Option<&str>) -> char {
+    value.and_then(|value| value.chars().next()).unwrap_or(' ')
+}
+
+pub enum MarcWriter {
+    Binary,
+    Xml,
+}
+
+impl Encoder<ExportedBook> for MarcWriter {
+    fn header(&mut self) -> Vec<u8> {
+        match self {
+            Self::Binary => Vec::new(),
+            Self::Xml => format!(
+                "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+                 <collection xmlns=\"{MARCXML_NAMESPACE}\">\n"
+            )
+            .into_bytes(),
+        }
+    }
+
+    fn encode(&mut self, book: ExportedBook) -> crate::Result<Vec<u8>> {
+        let record = Record::from_book(&book);
+        Ok(match self {
+            Self::Binary => record.to_binary(),
+            Self::Xml => record.to_xml().into_bytes(),
+        })
+    }
+
+    fn footer(&mut self) -> Vec<u8> {
+        match self {
+            Self::Binary => Vec::new(),
+            Self::Xml => b"</collection>\n".to_vec(),
+        }
+    }
+}
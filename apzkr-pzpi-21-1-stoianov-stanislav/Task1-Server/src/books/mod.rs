@@ -1,11 +1,19 @@
 mod author;
 mod genre;
+mod isbn;
 mod name;
 mod owner;
 mod year;
 
 mod add;
 mod delete;
+mod export;
+mod import;
+mod lookup;
+mod marc;
+mod opds;
+mod score;
+mod search;
 mod update;
 mod view;
 
@@ -13,22 +21,36 @@ pub use owner::check_owns;
 
 pub use add::add_book;
 pub use delete::delete_book;
+pub use export::export_books;
+pub use import::import_books;
+pub use lookup::find_by_isbn;
+pub use opds::{opds_book, opds_books, opds_facets, opds_root, opds_search};
+pub use score::lending_score;
+pub use search::search_books;
 pub use update::update_book;
-pub use view::{list_library_books, view_book};
+pub use view::{list_library_books, view_book, view_library_book};
 
 use serde::{Deserialize, Serialize};
+use serde_with::{serde_as, NoneAsEmptyString};
 
-use crate::id::{tag, Id};
+use crate::{
+    export::{Columns, ExportFormat},
+    id::{tag, Id},
+    libraries::{self, LibraryId},
+};
+
+pub use self::{
+    author::Author, genre::Genre, isbn::Isbn, name::Name, year::Year,
+};
 
 use self::{
-    author::{Author, UnvalidatedAuthor},
-    genre::{Genre, UnvalidatedGenre},
-    name::{Name, UnvalidatedName},
-    year::{UnvalidatedYear, Year},
+    author::UnvalidatedAuthor, genre::UnvalidatedGenre, isbn::UnvalidatedIsbn,
+    name::UnvalidatedName, year::UnvalidatedYear,
 };
 
 pub type BookId = Id<{ tag("book") }>;
 
+#[serde_as]
 #[derive(Clone, Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct NewBook {
@@ -36,6 +58,9 @@ pub struct NewBook {
     pub name: UnvalidatedName,
     pub genre: UnvalidatedGenre,
     pub author: UnvalidatedAuthor,
+    #[serde_as(as = "NoneAsEmptyString")]
+    #[serde(default)]
+    pub isbn: Option<UnvalidatedIsbn>,
 }
 
 #[derive(Clone, Debug, Serialize)]
@@ -46,8 +71,52 @@ pub struct Book {
     pub name: Name,
     pub genre: Genre,
     pub author: Author,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub isbn: Option<Isbn>,
 }
 
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LibraryBook {
+    pub id: BookId,
+    pub year: Year,
+    pub name: Name,
+    pub genre: Genre,
+    pub author: Author,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub isbn: Option<Isbn>,
+    pub available: i64,
+    pub total: i64,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BookFilter {
+    pub genre: Option<UnvalidatedGenre>,
+    pub author: Option<UnvalidatedAuthor>,
+    pub q: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BookSort {
+    #[default]
+    Name,
+    Year,
+    Author,
+}
+
+impl BookSort {
+    pub fn column(self) -> &'static str {
+        match self {
+            Self::Name => "name",
+            Self::Year => "year",
+            Self::Author => "author",
+        }
+    }
+}
+
+#[serde_as]
 #[derive(Clone, Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct UpdateBook {
@@ -55,4 +124,163 @@ pub struct UpdateBook {
     pub name: UnvalidatedName,
     pub genre: UnvalidatedGenre,
     pub author: UnvalidatedAuthor,
+    #[serde_as(as = "NoneAsEmptyString")]
+    #[serde(default)]
+    pub isbn: Option<UnvalidatedIsbn>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportedBook {
+    pub id: BookId,
+    pub year: Year,
+    pub name: Name,
+    pub genre: Genre,
+    pub author: Author,
+    pub isbn: Option<Isbn>,
+    pub copies: i64,
+}
+
+impl Columns for ExportedBook {
+    const COLUMNS: &'static [&'static str] =
+        &["id", "year", "name", "genre", "author", "isbn", "copies"];
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BookFormat {
+    #[default]
+    Csv,
+    Jsonl,
+    Marc,
+    Marcxml,
+}
+
+impl BookFormat {
+    pub fn content_type(self) -> &'static str {
+        match self {
+            Self::Csv => ExportFormat::Csv.content_type(),
+            Self::Jsonl => ExportFormat::Jsonl.content_type(),
+            Self::Marc => "application/marc",
+            Self::Marcxml => "application/marcxml+xml",
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Csv => ExportFormat::Csv.extension(),
+            Self::Jsonl => ExportFormat::Jsonl.extension(),
+            Self::Marc => "mrc",
+            Self::Marcxml => "xml",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BookExportOptions {
+    #[serde(default)]
+    pub format: BookFormat,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportOptions {
+    #[serde(default)]
+    pub dry_run: bool,
+    #[serde(default)]
+    pub format: BookFormat,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportReport {
+    pub dry_run: bool,
+    pub imported: usize,
+    pub errors: Vec<RowError>,
+}
+
+impl ImportReport {
+    pub fn failed(dry_run: bool, errors: Vec<RowError>) -> Self {
+        Self {
+            dry_run,
+            imported: 0,
+            errors,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RowError {
+    pub row: u64,
+    pub error: String,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum OpdsFacet {
+    Genre,
+    Author,
+}
+
+impl OpdsFacet {
+    pub fn key(self) -> &'static str {
+        match self {
+            Self::Genre => "genre",
+            Self::Author => "author",
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct OpdsDocument {
+    pub content_type: &'static str,
+    pub body: String,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BookLocation {
+    pub id: BookId,
+    pub library_id: LibraryId,
+    pub library_name: libraries::Name,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LendingScore {
+    pub book_id: BookId,
+    pub score: i64,
+    pub popularity: i64,
+    pub total_lendings: i64,
+    pub average_lateness_days: f64,
+    pub queue_length: i64,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchQuery {
+    pub q: String,
+    pub library_id: Option<LibraryId>,
+    pub genre: Option<UnvalidatedGenre>,
+    pub year_from: Option<UnvalidatedYear>,
+    pub year_to: Option<UnvalidatedYear>,
+    pub available: Option<bool>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchResults {
+    pub total: i64,
+    pub groups: Vec<LibraryHits>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LibraryHits {
+    pub library_id: LibraryId,
+    pub library_name: libraries::Name,
+    pub books: Vec<LibraryBook>,
 }
@@ -0,0 +1,362 @@
+use chrono::{SecondsFormat, Utc};
+use quick_xml::escape::escape;
+
+use crate::{
+    database::Database, libraries::LibraryId, page::PageRequest,
+    state::AppState, telemetry, Error,
+};
+
+use super::{
+    list_library_books, view_library_book, BookFilter, BookId, BookSort,
+    LibraryBook, OpdsDocument, OpdsFacet,
+};
+
+const NAVIGATION: &str =
+    "application/atom+xml;profile=opds-catalog;kind=navigation";
+const ACQUISITION: &str =
+    "application/atom+xml;profile=opds-catalog;kind=acquisition";
+const ENTRY: &str = "application/atom+xml;type=entry;profile=opds-catalog";
+const OPENSEARCH: &str = "application/opensearchdescription+xml";
+const NAMESPACES: &str = "xmlns=\"http://www.w3.org/2005/Atom\" \
+                          xmlns:dc=\"http://purl.org/dc/terms/\" \
+                          xmlns:opds=\"http://opds-spec.org/2010/catalog\"";
+const SHORT_NAME_LEN: usize = 16;
+
+#[tracing::instrument(skip(state))]
+pub async fn opds_root(
+    library_id: LibraryId,
+    state: AppState,
+) -> crate::Result<OpdsDocument> {
+    let name = library_name(library_id, &state).await?;
+    let base = base_path(library_id);
+    let mut feed = Feed::new(&format!("{base}/opds"), &name, NAVIGATION, &base);
+    feed.navigation_entry(
+        &format!("{base}/opds/books"),
+        "All books",
+        "Every book in the catalog",
+        ACQUISITION,
+    );
+    feed.navigation_entry(
+        &format!("{base}/opds/genres"),
+        "By genre",
+        "Books grouped by genre",
+        NAVIGATION,
+    );
+    feed.navigation_entry(
+        &format!("{base}/opds/authors"),
+        "By author",
+        "Books grouped by author",
+        NAVIGATION,
+    );
+    Ok(feed.finish(NAVIGATION))
+}
+
+#[tracing::instrument(skip(state))]
+pub async fn opds_facets(
+    library_id: LibraryId,
+    facet: OpdsFacet,
+    state: AppState,
+) -> crate::Result<OpdsDocument> {
+    let name = library_name(library_id, &state).await?;
+    let db_library_id = library_id.sql_id(&state.id_cipher)?;
+    let values =
+        get_facet_values(db_library_id, facet, &state.database).await?;
+    let base = base_path(library_id);
+    let (path, title) = match facet {
+        OpdsFacet::Genre => ("genres", "By genre"),
+        OpdsFacet::Author => ("authors", "By author"),
+    };
+    let mut feed = Feed::new(
+        &format!("{base}/opds/{path}"),
+        &format!("{name}: {title}"),
+        NAVIGATION,
+        &base,
+    );
+    for value in values {
+        let key = facet.key();
+        let query = serde_urlencoded::to_string([(key, &value.value)])
+            .map_err(anyhow::Error::from)?;
+        feed.navigation_entry(
+            &format!("{base}/opds/books?{query}"),
+            &value.value,
+            &match value.books {
+                1 => "1 book".to_owned(),
+                books => format!("{books} books"),
+            },
+            ACQUISITION,
+        );
+    }
+    Ok(feed.finish(NAVIGATION))
+}
+
+#[tracing::instrument(skip(state))]
+pub async fn opds_books(
+    library_id: LibraryId,
+    filter: BookFilter,
+    page: PageRequest<BookId, BookSort>,
+    state: AppState,
+) -> crate::Result<OpdsDocument> {
+    let name = library_name(library_id, &state).await?;
+    let base = base_path(library_id);
+    let mut query = Vec::new();
+    let mut title = name.clone();
+    for (key, value) in [
+        ("genre", &filter.genre),
+        ("author", &filter.author),
+        ("q", &filter.q),
+    ] {
+        if let Some(value) = value {
+            query.push((key, value.clone()));
+            title = format!("{title}: {value}");
+        }
+    }
+    let books =
+        list_library_books(library_id, filter, page, state.clone()).await?;
+    let href = |query: &[(&str, String)]| {
+        serde_urlencoded::to_string(query)
+            .map(|query| match query.is_empty() {
+                true => format!("{base}/opds/books"),
+                false => format!("{base}/opds/books?{query}"),
+            })
+            .map_err(anyhow::Error::from)
+    };
+    let mut feed = Feed::new(&href(&query)?, &title, ACQUISITION, &base);
+    if let Some(cursor) = books.next_cursor {
+        query.push(("cursor", cursor.to_string()));
+        feed.link("next", &href(&query)?, ACQUISITION);
+    }
+    for book in books.items {
+        feed.acquisition_entry(&base, &book);
+    }
+    Ok(feed.finish(ACQUISITION))
+}
+
+#[tracing::instrument(skip(state))]
+pub async fn opds_book(
+    library_id: LibraryId,
+    book_id: BookId,
+    state: AppState,
+) -> crate::Result<OpdsDocument> {
+    let book = view_library_book(library_id, book_id, &state).await?;
+    let base = base_path(library_id);
+    let mut entry = Feed {
+        xml: format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+             <entry {NAMESPACES}>"
+        ),
+    };
+    entry.acquisition_entry_body(&base, &book);
+    entry.xml.push_str("</entry>\n");
+    Ok(OpdsDocument {
+        content_type: ENTRY,
+        body: entry.xml,
+    })
+}
+
+#[tracing::instrument(skip(state))]
+pub async fn opds_search(
+    library_id: LibraryId,
+    state: AppState,
+) -> crate::Result<OpdsDocument> {
+    let name = library_name(library_id, &state).await?;
+    let base = base_path(library_id);
+    let body = format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <OpenSearchDescription \
+         xmlns=\"http://a9.com/-/spec/opensearch/1.1/\">\
+         <ShortName>{short_name}</ShortName>\
+         <Description>Search the {name} catalog</Description>\
+         <InputEncoding>UTF-8</InputEncoding>\
+         <OutputEncoding>UTF-8</OutputEncoding>\
+         <Url type=\"{ACQUISITION}\" \
+         template=\"{base}/opds/books?q={{searchTerms}}\"/>\
+         </OpenSearchDescription>\n",
+        short_name =
+            escape(&name.chars().take(SHORT_NAME_LEN).collect::<String>()),
+        name = escape(&name),
+        base = escape(&base),
+    );
+    Ok(OpdsDocument {
+        content_type: OPENSEARCH,
+        body,
+    })
+}
+
+struct Feed {
+    xml: String,
+}
+
+impl Feed {
+    fn new(href: &str, title: &str, kind: &str, base: &str) -> Self {
+        let mut feed = Self {
+            xml: format!(
+                "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+                 <feed {NAMESPACES}>"
+            ),
+        };
+        feed.element("id", &format!("urn:libmarse:{href}"));
+        feed.element("title", title);
+        feed.element("updated", &now());
+        feed.link("self", href, kind);
+        feed.link("start", &format!("{base}/opds"), NAVIGATION);
+        feed.link("search", &format!("{base}/opds/search.xml"), OPENSEARCH);
+        feed
+    }
+
+    fn element(&mut self, name: &str, text: &str) {
+        self.xml
+            .push_str(&format!("<{name}>{}</{name}>", escape(text)));
+    }
+
+    fn link(&mut self, rel: &str, href: &str, kind: &str) {
+        self.xml.push_str(&format!(
+            "<link rel=\"{}\" href=\"{}\" type=\"{}\"/>",
+            escape(rel),
+            escape(href),
+            escape(kind)
+        ));
+    }
+
+    fn navigation_entry(
+        &mut self,
+        href: &str,
+        title: &str,
+        content: &str,
+        kind: &str,
+    ) {
+        self.xml.push_str("<entry>");
+        self.element("id", &format!("urn:libmarse:{href}"));
+        self.element("title", title);
+        self.element("updated", &now());
+        self.xml.push_str(&format!(
+            "<content type=\"text\">{}</content>",
+            escape(content)
+        ));
+        self.link("subsection", href, kind);
+        self.xml.push_str("</entry>");
+    }
+
+    fn acquisition_entry(&mut self, base: &str, book: &LibraryBook) {
+        self.xml.push_str("<entry>");
+        self.acquisition_entry_body(base, book);
+        self.xml.push_str("</entry>");
+    }
+
+    fn acquisition_entry_body(&mut self, base: &str, book: &LibraryBook) {
+        let name = String::from(book.name.clone());
+        let author = String::from(book.author.clone());
+        let genre = String::from(book.genre.clone());
+        let year = i16::from(book.year.clone());
+        self.element("id", &format!("urn:libmarse:book:{}", book.id));
+        self.element("title", &name);
+        self.element("updated", &now());
+        self.xml.push_str(&format!(
+            "<author><name>{}</name></author>",
+            escape(&author)
+        ));
+        self.xml.push_str(&format!(
+            "<category term=\"{genre}\" label=\"{genre}\"/>",
+            genre = escape(&genre)
+        ));
+        self.element("dc:issued", &year.to_string());
+        if let Some(isbn) = book.isbn.clone() {
+            let isbn = String::from(isbn);
+            self.element("dc:identifier", &format!("urn:isbn:{isbn}"));
+        }
+        self.xml.push_str(&format!(
+            "<content type=\"text\">{} of {} copies available</content>",
+            book.available, book.total
+        ));
+        let status = match book.available > 0 {
+            true => "available",
+            false => "unavailable",
+        };
+        self.xml.push_str(&format!(
+            "<link rel=\"http://opds-spec.org/acquisition/borrow\" \
+             href=\"{}\" type=\"{ENTRY}\">\
+             <opds:availability status=\"{status}\"/>\
+             <opds:copies total=\"{}\" available=\"{}\"/>\
+             </link>",
+            escape(&format!("{base}/opds/books/{}", book.id)),
+            book.total,
+            book.available,
+        ));
+    }
+
+    fn finish(mut self, kind: &'static str) -> OpdsDocument {
+        self.xml.push_str("</feed>\n");
+        OpdsDocument {
+            content_type: kind,
+            body: self.xml,
+        }
+    }
+}
+
+fn base_path(library_id: LibraryId) -> String {
+    format!("/libraries/{library_id}")
+}
+
+fn now() -> String {
+    Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+async fn library_name(
+    library_id: LibraryId,
+    state: &AppState,
+) -> crate::Result<String> {
+    let library_id = library_id
+        .sql_id(&state.id_cipher)
+        .map_err(|_| Error::NotFound)
+        .inspect_err(telemetry::debug)?;
+    get_library_name(library_id, &state.database)
+        .await?
+        .map(|name| name.0)
+        .ok_or(Error::NotFound)
+        .inspect_err(telemetry::debug)
+}
+
+#[derive(Clone, Debug, sqlx::FromRow)]
+struct DbFacetValue {
+    value: String,
+    books: i64,
+}
+
+#[tracing::instrument(skip(db), err(Debug))]
+async fn get_library_name(
+    library_id: i64,
+    db: &Database,
+) -> crate::Result<Option<(String,)>> {
+    sqlx::query_as(
+        "
+        select name
+        from libraries
+        where id = $1;
+        ",
+    )
+    .bind(library_id)
+    .fetch_optional(db)
+    .await
+    .map_err(Error::from)
+}
+
+#[tracing::instrument(skip(db), err(Debug))]
+async fn get_facet_values(
+    library_id: i64,
+    facet: OpdsFacet,
+    db: &Database,
+) -> crate::Result<Vec<DbFacetValue>> {
+    sqlx::query_as(&format!(
+        "
+        select {key} as value, count(*) as books
+        from books
+        where library_id = $1
+        group by {key}
+        order by {key};
+        ",
+        key = facet.key(),
+    ))
+    .bind(library_id)
+    .fetch_all(db)
+    .await
+    .map_err(Error::from)
+}
@@ -0,0 +1,78 @@
+use crate::{database::Database, state::AppState, telemetry, Error};
+
+use super::{BookId, LendingScore};
+
+const POPULARITY_WINDOW_DAYS: i32 = 90;
+const QUEUE_WEIGHT: i64 = 2;
+const MAX_SCORE: i64 = 30;
+
+#[tracing::instrument(skip(state))]
+pub async fn lending_score(
+    book_id: BookId,
+    state: AppState,
+) -> crate::Result<LendingScore> {
+    let db_book_id = book_id
+        .sql_id(&state.id_cipher)
+        .map_err(|_| Error::NotFound)
+        .inspect_err(telemetry::debug)?;
+    let stats = get_lending_stats(db_book_id, &state.database)
+        .await?
+        .ok_or(Error::NotFound)
+        .inspect_err(telemetry::debug)?;
+    let score = stats.popularity
+        + QUEUE_WEIGHT * stats.queue_length
+        + stats.average_lateness_days.ceil() as i64;
+    Ok(LendingScore {
+        book_id,
+        score: score.min(MAX_SCORE),
+        popularity: stats.popularity,
+        total_lendings: stats.total_lendings,
+        average_lateness_days: stats.average_lateness_days,
+        queue_length: stats.queue_length,
+    })
+}
+
+#[derive(Clone, Debug, sqlx::FromRow)]
+struct DbLendingStats {
+    popularity: i64,
+    total_lendings: i64,
+    average_lateness_days: f64,
+    queue_length: i64,
+}
+
+#[tracing::instrument(skip(db), err(Debug))]
+async fn get_lending_stats(
+    book_id: i64,
+    db: &Database,
+) -> crate::Result<Option<DbLendingStats>> {
+    sqlx::query_as(
+        "
+        select
+          count(l.id) filter (
+            where l.lent_on >= current_date - $2
+          ) as popularity,
+          count(l.id) as total_lendings,
+          coalesce(avg(
+            greatest(coalesce(l.returned_on, current_date) - l.due, 0)
+          ) filter (
+            where l.returned_on is not null or l.due < current_date
+          ), 0)::float8 as average_lateness_days,
+          (
+            select count(*)
+            from holds h
+            where h.book_id = b.id
+              and h.status in ('waiting', 'ready')
+          ) as queue_length
+        from books b
+        left join copies c on c.book_id = b.id
+        left join lendings l on l.copy_id = c.id
+        where b.id = $1
+        group by b.id;
+        ",
+    )
+    .bind(book_id)
+    .bind(POPULARITY_WINDOW_DAYS)
+    .fetch_optional(db)
+    .await
+    .map_err(Error::from)
+}
@@ -0,0 +1,165 @@
+use crate::{
+    database::Database,
+    libraries::{self, LibraryId},
+    state::AppState,
+    telemetry, Error,
+};
+
+use super::{
+    author::Author, genre::Genre, isbn::Isbn, name::Name, year::Year, BookId,
+    LibraryBook, LibraryHits, SearchQuery, SearchResults,
+};
+
+const DEFAULT_LIMIT: i64 = 20;
+const MAX_LIMIT: i64 = 100;
+
+#[tracing::instrument(skip(state))]
+pub async fn search_books(
+    query: SearchQuery,
+    state: AppState,
+) -> crate::Result<SearchResults> {
+    let terms = prefix_terms(&query.q)
+        .ok_or(Error::Validation("search query is empty"))
+        .inspect_err(telemetry::debug)?;
+    let library_id = query
+        .library_id
+        .map(|id| id.sql_id(&state.id_cipher))
+        .transpose()
+        .map_err(|_| Error::NotFound)
+        .inspect_err(telemetry::debug)?;
+    let search = DbSearch {
+        terms,
+        library_id,
+        genre: query.genre,
+        year_from: query.year_from,
+        year_to: query.year_to,
+        available: query.available,
+        limit: query.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT),
+        offset: query.offset.unwrap_or_default().max(0),
+    };
+    let hits = find_books(&search, &state.database).await?;
+    let total = hits.first().map_or(0, |hit| hit.total_hits);
+    let mut groups = Vec::<(i64, LibraryHits)>::new();
+    for hit in hits {
+        let book = LibraryBook {
+            id: BookId::new(hit.id, &state.id_cipher),
+            year: hit.year,
+            name: hit.name,
+            genre: hit.genre,
+            author: hit.author,
+            isbn: hit.isbn,
+            available: hit.available,
+            total: hit.total,
+        };
+        match groups.iter_mut().find(|(id, _)| *id == hit.library_id) {
+            Some((_, group)) => group.books.push(book),
+            None => groups.push((
+                hit.library_id,
+                LibraryHits {
+                    library_id: LibraryId::new(
+                        hit.library_id,
+                        &state.id_cipher,
+                    ),
+                    library_name: hit.library_name,
+                    books: vec![book],
+                },
+            )),
+        }
+    }
+    Ok(SearchResults {
+        total,
+        groups: groups.into_iter().map(|(_, group)| group).collect(),
+    })
+}
+
+pub fn prefix_terms(q: &str) -> Option<String> {
+    let terms = q
+        .split_whitespace()
+        .map(|term| {
+            term.chars()
+                .filter(|c| c.is_alphanumeric())
+                .collect::<String>()
+        })
+        .filter(|term| !term.is_empty())
+        .map(|term| format!("{term}:*"))
+        .collect::<Vec<_>>();
+    (!terms.is_empty()).then(|| terms.join(" & "))
+}
+
+#[derive(Clone, Debug)]
+struct DbSearch {
+    terms: String,
+    library_id: Option<i64>,
+    genre: Option<String>,
+    year_from: Option<i16>,
+    year_to: Option<i16>,
+    available: Option<bool>,
+    limit: i64,
+    offset: i64,
+}
+
+#[derive(Clone, Debug, sqlx::FromRow)]
+struct DbHit {
+    id: i64,
+    year: Year,
+    name: Name,
+    genre: Genre,
+    author: Author,
+    isbn: Option<Isbn>,
+    available: i64,
+    total: i64,
+    library_id: i64,
+    library_name: libraries::Name,
+    total_hits: i64,
+}
+
+#[tracing::instrument(skip(db), err(Debug))]
+async fn find_books(
+    search: &DbSearch,
+    db: &Database,
+) -> crate::Result<Vec<DbHit>> {
+    sqlx::query_as(
+        "
+        select b.id, b.year, b.name, b.genre, b.author, b.isbn,
+          n.available, n.total,
+          lib.id as library_id, lib.name as library_name,
+          count(*) over () as total_hits
+        from books b
+        cross join to_tsquery('simple', $1) query
+        join libraries lib on lib.id = b.library_id
+        cross join lateral (
+          select
+            count(*) filter (
+              where not exists (
+                select from lendings l
+                where l.copy_id = c.id
+                  and l.returned_on is null
+              )
+            ) as available,
+            count(*) as total
+          from copies c
+          where c.book_id = b.id
+        ) n
+        where b.search @@ query
+          and ($2::bigint is null or b.library_id = $2)
+          and ($3::varchar is null or lower(b.genre) = lower($3))
+          and ($4::smallint is null or b.year >= $4)
+          and ($5::smallint is null or b.year <= $5)
+          and ($6::boolean is null or (n.available > 0) = $6)
+        order by ts_rank(b.search, query) desc, b.id
+        limit $7
+        offset $8;
+        ",
+    )
+    .bind(&search.terms)
+    .bind(search.library_id)
+    .bind(&search.genre)
+    .bind(search.year_from)
+    .bind(search.year_to)
+    .bind(search.available)
+    .bind(search.limit)
+    .bind(search.offset)
+    .fetch_all(db)
+    .await
+    .map_err(Error::from)
+}
@@ -1,11 +1,16 @@
+use sqlx::error::ErrorKind;
+
 use crate::{
-    auth::UserId, database::Database, libraries::LibraryId, state::AppState,
+    auth::UserId,
+    database::{error_kind, Database},
+    libraries::LibraryId,
+    state::AppState,
     telemetry, Error,
 };
 
 use super::{
-    author::Author, check_owns, genre::Genre, name::Name, year::Year, BookId,
-    UpdateBook,
+    author::Author, check_owns, genre::Genre, isbn::Isbn, name::Name,
+    year::Year, BookId, UpdateBook,
 };
 
 #[tracing::instrument(skip(state))]
@@ -35,6 +40,7 @@ pub async fn update_book(
         name: Name::new(book.name)?,
         genre: Genre::new(book.genre)?,
         author: Author::new(book.author)?,
+        isbn: book.isbn.map(Isbn::new).transpose()?,
     };
     update_db_book(book, &state.database).await
 }
@@ -46,6 +52,7 @@ struct DbBook {
     name: Name,
     genre: Genre,
     author: Author,
+    isbn: Option<Isbn>,
 }
 
 #[tracing::instrument(skip(db))]
@@ -53,25 +60,32 @@ async fn update_db_book(book: DbBook, db: &Database) -> crate::Result<()> {
     match sqlx::query(
         "
         update books
-        set (year, name, genre, author)
-          = ($1, $2, $3, $4)
-        where id = $5;
+        set (year, name, genre, author, isbn)
+          = ($1, $2, $3, $4, $5)
+        where id = $6;
         ",
     )
     .bind(&book.year)
     .bind(&book.name)
     .bind(&book.genre)
     .bind(&book.author)
+    .bind(&book.isbn)
     .bind(book.id)
     .execute(db)
     .await
-    .map_err(Error::from)
-    .inspect_err(telemetry::error)?
-    .rows_affected()
     {
-        0 => Err(Error::NotFound),
-        1 => Ok(()),
-        _ => unreachable!(),
+        Err(e) if error_kind(&e) == Some(ErrorKind::UniqueViolation) => {
+            Err(Error::IsbnTaken).inspect_err(telemetry::debug)
+        }
+        other => match other
+            .map_err(Error::from)
+            .inspect_err(telemetry::error)?
+            .rows_affected()
+        {
+            0 => Err(Error::NotFound),
+            1 => Ok(()),
+            _ => unreachable!(),
+        }
+        .inspect_err(telemetry::debug),
     }
-    .inspect_err(telemetry::debug)
 }
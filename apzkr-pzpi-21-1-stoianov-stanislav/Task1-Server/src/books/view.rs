@@ -1,31 +1,57 @@
 use crate::{
-    database::Database, libraries::LibraryId, state::AppState, telemetry, Error,
+    database::Database,
+    libraries::LibraryId,
+    page::{check_cursor, Page, PageRequest},
+    state::AppState,
+    telemetry, Error,
 };
 
 use super::{
-    author::Author, genre::Genre, name::Name, year::Year, Book, BookId,
+    author::Author, genre::Genre, isbn::Isbn, name::Name, search::prefix_terms,
+    year::Year, Book, BookFilter, BookId, BookSort, LibraryBook,
 };
 
 #[tracing::instrument(skip(state))]
 pub async fn list_library_books(
     library_id: LibraryId,
+    filter: BookFilter,
+    page: PageRequest<BookId, BookSort>,
     state: AppState,
-) -> crate::Result<Vec<Book>> {
+) -> crate::Result<Page<LibraryBook, BookId>> {
     let library_id = library_id.sql_id(&state.id_cipher)?;
-    get_library_books(library_id, &state.database)
-        .await
-        .map(|books| {
-            books
-                .into_iter()
-                .map(|book| Book {
-                    id: BookId::new(book.id, &state.id_cipher),
-                    year: book.year,
-                    name: book.name,
-                    genre: book.genre,
-                    author: book.author,
-                })
-                .collect()
-        })
+    let cursor = page.sql_cursor(&state.id_cipher)?;
+    check_cursor("books", cursor, &state.database).await?;
+    let limit = page.limit();
+    let filter = DbFilter {
+        genre: filter.genre,
+        author: filter.author,
+        terms: filter.q.as_deref().and_then(prefix_terms),
+    };
+    get_library_books(
+        library_id,
+        &filter,
+        cursor,
+        limit,
+        page.sort,
+        &state.database,
+    )
+    .await
+    .map(|books| {
+        let books = books
+            .into_iter()
+            .map(|book| LibraryBook {
+                id: BookId::new(book.id, &state.id_cipher),
+                year: book.year,
+                name: book.name,
+                genre: book.genre,
+                author: book.author,
+                isbn: book.isbn,
+                available: book.available,
+                total: book.total,
+            })
+            .collect();
+        Page::new(books, limit, |book| book.id)
+    })
 }
 
 #[tracing::instrument(skip(state))]
@@ -47,9 +73,42 @@ pub async fn view_book(
             name: book.name,
             genre: book.genre,
             author: book.author,
+            isbn: book.isbn,
         })
 }
 
+#[tracing::instrument(skip(state))]
+pub async fn view_library_book(
+    library_id: LibraryId,
+    book_id: BookId,
+    state: &AppState,
+) -> crate::Result<LibraryBook> {
+    let library_id = library_id.sql_id(&state.id_cipher)?;
+    let book_id = book_id.sql_id(&state.id_cipher)?;
+    get_library_book(library_id, book_id, &state.database)
+        .await
+        .and_then(|book| {
+            book.ok_or(Error::NotFound).inspect_err(telemetry::debug)
+        })
+        .map(|book| LibraryBook {
+            id: BookId::new(book.id, &state.id_cipher),
+            year: book.year,
+            name: book.name,
+            genre: book.genre,
+            author: book.author,
+            isbn: book.isbn,
+            available: book.available,
+            total: book.total,
+        })
+}
+
+#[derive(Clone, Debug)]
+struct DbFilter {
+    genre: Option<String>,
+    author: Option<String>,
+    terms: Option<String>,
+}
+
 #[derive(Clone, Debug, sqlx::FromRow)]
 struct DbBook {
     id: i64,
@@ -57,21 +116,61 @@ struct DbBook {
     name: Name,
     genre: Genre,
     author: Author,
+    isbn: Option<Isbn>,
+}
+
+#[derive(Clone, Debug, sqlx::FromRow)]
+struct DbLibraryBook {
+    id: i64,
+    year: Year,
+    name: Name,
+    genre: Genre,
+    author: Author,
+    isbn: Option<Isbn>,
+    available: i64,
+    total: i64,
 }
 
 #[tracing::instrument(skip(db), err(Debug))]
 async fn get_library_books(
     library_id: i64,
+    filter: &DbFilter,
+    cursor: Option<i64>,
+    limit: i64,
+    sort: BookSort,
     db: &Database,
-) -> crate::Result<Vec<DbBook>> {
-    sqlx::query_as(
+) -> crate::Result<Vec<DbLibraryBook>> {
+    sqlx::query_as(&format!(
         "
-        select id, year, name, genre, author
-        from books
-        where library_id = $1;
+        select b.id, b.year, b.name, b.genre, b.author, b.isbn,
+          count(c.id) filter (
+            where not exists (
+              select from lendings l
+              where l.copy_id = c.id
+                and l.returned_on is null
+            )
+          ) as available,
+          count(c.id) as total
+        from books b
+        left join copies c on c.book_id = b.id
+        where b.library_id = $1
+          and ($2::varchar is null or b.genre = $2)
+          and ($3::varchar is null or b.author = $3)
+          and ($4::varchar is null or b.search @@ to_tsquery('simple', $4))
+          and ($5::bigint is null
+            or (b.{key}, b.id) > (select {key}, id from books where id = $5))
+        group by b.id
+        order by b.{key}, b.id
+        limit $6 + 1;
         ",
-    )
+        key = sort.column(),
+    ))
     .bind(library_id)
+    .bind(&filter.genre)
+    .bind(&filter.author)
+    .bind(&filter.terms)
+    .bind(cursor)
+    .bind(limit)
     .fetch_all(db)
     .await
     .map_err(Error::from)
@@ -85,7 +184,7 @@ async fn get_book(
 ) -> crate::Result<Option<DbBook>> {
     sqlx::query_as(
         "
-        select id, year, name, genre, author
+        select id, year, name, genre, author, isbn
         from books
         where id = $1
           and library_id = $2;
@@ -97,3 +196,34 @@ async fn get_book(
     .await
     .map_err(Error::from)
 }
+
+#[tracing::instrument(skip(db), err(Debug))]
+async fn get_library_book(
+    library_id: i64,
+    book_id: i64,
+    db: &Database,
+) -> crate::Result<Option<DbLibraryBook>> {
+    sqlx::query_as(
+        "
+        select b.id, b.year, b.name, b.genre, b.author, b.isbn,
+          count(c.id) filter (
+            where not exists (
+              select from lendings l
+              where l.copy_id = c.id
+                and l.returned_on is null
+            )
+          ) as available,
+          count(c.id) as total
+        from books b
+        left join copies c on c.book_id = b.id
+        where b.id = $1
+          and b.library_id = $2
+        group by b.id;
+        ",
+    )
+    .bind(book_id)
+    .bind(library_id)
+    .fetch_optional(db)
+    .await
+    .map_err(Error::from)
+}
@@ -32,6 +32,7 @@ pub struct AppConfig {
     pub jwt: JwtConfig,
     pub hasher: HasherConfig,
     pub backup: BackupConfig,
+    pub idempotency: IdempotencyConfig,
 }
 
 #[derive(Clone, Debug, Deserialize)]
@@ -74,6 +75,15 @@ pub struct BackupConfig {
     pub args: Vec<String>,
 }
 
+#[serde_as]
+#[derive(Clone, Debug, Deserialize)]
+pub struct IdempotencyConfig {
+    #[serde_as(as = "DurationSeconds<u64>")]
+    pub ttl: Duration,
+    #[serde_as(as = "DurationSeconds<u64>")]
+    pub lock_timeout: Duration,
+}
+
 impl Config {
     pub fn init() -> anyhow::Result<Config> {
         config::Config::builder()
@@ -0,0 +1,125 @@
+use sqlx::error::ErrorKind;
+
+use crate::{
+    auth::UserId,
+    books::{check_owns, BookId},
+    database::{error_kind, Database, Transaction},
+    libraries::LibraryId,
+    state::AppState,
+    telemetry, Error,
+};
+
+use super::{
+    barcode::Barcode, condition::Condition, shelf_location::ShelfLocation,
+    NewCopy,
+};
+
+#[tracing::instrument(skip(state))]
+pub async fn add_copy(
+    owner_id: UserId,
+    library_id: LibraryId,
+    book_id: BookId,
+    copy: NewCopy,
+    state: AppState,
+) -> crate::Result<()> {
+    let owner_id = owner_id
+        .sql_id(&state.id_cipher)
+        .map_err(|_| Error::LoggedOff)
+        .inspect_err(telemetry::debug)?;
+    let library_id = library_id
+        .sql_id(&state.id_cipher)
+        .map_err(|_| Error::NotFound)
+        .inspect_err(telemetry::debug)?;
+    check_owns(owner_id, library_id, &state.database).await?;
+    let book_id = book_id
+        .sql_id(&state.id_cipher)
+        .map_err(|_| Error::NotFound)
+        .inspect_err(telemetry::debug)?;
+    let copy = DbCopy {
+        library_id,
+        book_id,
+        barcode: copy.barcode.map(Barcode::new).transpose()?,
+        shelf_location: ShelfLocation::new(copy.shelf_location)?,
+        condition: copy.condition,
+    };
+    insert_copy(&copy, &state.database).await
+}
+
+#[tracing::instrument(skip(tx), err(Debug))]
+pub async fn insert_default_copy(
+    book_id: i64,
+    tx: &mut Transaction<'_>,
+) -> crate::Result<()> {
+    match sqlx::query(
+        "
+        with next as (
+          select nextval('copies_id_seq') as id
+        )
+        insert into copies
+          (id, book_id, barcode, shelf_location, condition)
+        select id, $1, 'C' || lpad(id::text, 9, '0'), '', $2
+        from next;
+        ",
+    )
+    .bind(book_id)
+    .bind(Condition::New)
+    .execute(&mut **tx)
+    .await
+    {
+        Err(e) if error_kind(&e) == Some(ErrorKind::UniqueViolation) => {
+            Err(Error::BarcodeTaken).inspect_err(telemetry::debug)
+        }
+        other => other.map(|_| ()).map_err(Error::from),
+    }
+}
+
+#[derive(Clone, Debug)]
+struct DbCopy {
+    library_id: i64,
+    book_id: i64,
+    barcode: Option<Barcode>,
+    shelf_location: ShelfLocation,
+    condition: Condition,
+}
+
+#[tracing::instrument(skip(db))]
+async fn insert_copy(copy: &DbCopy, db: &Database) -> crate::Result<()> {
+    match sqlx::query(
+        "
+        with next as (
+          select nextval('copies_id_seq') as id
+        )
+        insert into copies
+          (id, book_id, barcode, shelf_location, condition)
+        select id, $1, coalesce($2, 'C' || lpad(id::text, 9, '0')), $3, $4
+        from next
+        where exists (
+          select from books
+          where id = $1
+            and library_id = $5
+        );
+        ",
+    )
+    .bind(copy.book_id)
+    .bind(&copy.barcode)
+    .bind(&copy.shelf_location)
+    .bind(copy.condition)
+    .bind(copy.library_id)
+    .execute(db)
+    .await
+    {
+        Err(e) if error_kind(&e) == Some(ErrorKind::UniqueViolation) => {
+            Err(Error::BarcodeTaken).inspect_err(telemetry::debug)
+        }
+        other => match other
+            .map_err(Error::from)
+            .inspect_err(telemetry::error)?
+            .rows_affected()
+        {
+            0 => Err(Error::NotFound),
+            1 => Ok(()),
+            _ => unreachable!(),
+        }
+        .inspect_err(telemetry::debug),
+    }
+}
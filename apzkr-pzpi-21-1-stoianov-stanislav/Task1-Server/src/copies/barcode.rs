@@ -0,0 +1,62 @@
+use serde::Serialize;
+
+use crate::Error;
+
+pub type UnvalidatedBarcode = String;
+
+#[derive(Clone, Debug, Default, sqlx::Type, Serialize)]
+#[sqlx(transparent)]
+#[serde(transparent)]
+pub struct Barcode(UnvalidatedBarcode);
+
+impl Barcode {
+    pub fn new(barcode: UnvalidatedBarcode) -> crate::Result<Self> {
+        if barcode.is_empty() {
+            Err(Error::Validation("barcode is empty"))
+        } else if barcode.len() > 32 {
+            Err(Error::Validation("barcode is too long"))
+        } else if !barcode
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-')
+        {
+            Err(Error::Validation("barcode must be alphanumeric"))
+        } else if is_generated(&barcode) {
+            Err(Error::Validation("barcode format is reserved"))
+        } else {
+            Ok(Self(barcode))
+        }
+    }
+
+    /// Like [`Barcode::new`], but keeps the barcode generated for the copy.
+    pub fn for_copy(
+        barcode: UnvalidatedBarcode,
+        copy_id: i64,
+    ) -> crate::Result<Self> {
+        if barcode == format!("C{copy_id:09}") {
+            Ok(Self(barcode))
+        } else {
+            Self::new(barcode)
+        }
+    }
+}
+
+/// Generated barcodes are `C` followed by the zero-padded copy id.
+fn is_generated(barcode: &str) -> bool {
+    barcode.len() == 10
+        && barcode.starts_with('C')
+        && barcode[1..].chars().all(|c| c.is_ascii_digit())
+}
+
+impl TryFrom<UnvalidatedBarcode> for Barcode {
+    type Error = Error;
+
+    fn try_from(value: UnvalidatedBarcode) -> Result<Self, Self::Error> {
+        Self::new(value)
+    }
+}
+
+impl From<Barcode> for UnvalidatedBarcode {
+    fn from(value: Barcode) -> Self {
+        value.0
+    }
+}
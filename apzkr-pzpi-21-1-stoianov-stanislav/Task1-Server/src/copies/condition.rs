@@ -0,0 +1,13 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, sqlx::Type)]
+#[sqlx(type_name = "varchar", rename_all = "snake_case")]
+#[serde(rename_all = "camelCase")]
+pub enum Condition {
+    New,
+    #[default]
+    Good,
+    Fair,
+    Poor,
+    Damaged,
+}
@@ -0,0 +1,91 @@
+use crate::{
+    auth::UserId,
+    books::{check_owns, BookId},
+    database::Database,
+    libraries::LibraryId,
+    state::AppState,
+    telemetry, Error,
+};
+
+use super::CopyId;
+
+#[tracing::instrument(skip(state))]
+pub async fn delete_copy(
+    owner_id: UserId,
+    library_id: LibraryId,
+    book_id: BookId,
+    copy_id: CopyId,
+    state: AppState,
+) -> crate::Result<()> {
+    let owner_id = owner_id
+        .sql_id(&state.id_cipher)
+        .map_err(|_| Error::LoggedOff)
+        .inspect_err(telemetry::debug)?;
+    let library_id = library_id
+        .sql_id(&state.id_cipher)
+        .map_err(|_| Error::NotFound)
+        .inspect_err(telemetry::debug)?;
+    check_owns(owner_id, library_id, &state.database).await?;
+    let book_id = book_id
+        .sql_id(&state.id_cipher)
+        .map_err(|_| Error::NotFound)
+        .inspect_err(telemetry::debug)?;
+    let copy_id = copy_id
+        .sql_id(&state.id_cipher)
+        .map_err(|_| Error::NotFound)
+        .inspect_err(telemetry::debug)?;
+    check_not_lent(copy_id, &state.database).await?;
+    delete_db_copy(library_id, book_id, copy_id, &state.database).await
+}
+
+#[tracing::instrument(skip(db))]
+async fn check_not_lent(copy_id: i64, db: &Database) -> crate::Result<()> {
+    sqlx::query_as::<_, ()>(
+        "
+        select from lendings
+        where copy_id = $1
+          and returned_on is null;
+        ",
+    )
+    .bind(copy_id)
+    .fetch_optional(db)
+    .await
+    .map_err(Error::from)
+    .and_then(|row| match row {
+        Some(_) => Err(Error::AlreadyLent).inspect_err(telemetry::debug),
+        None => Ok(()),
+    })
+}
+
+#[tracing::instrument(skip(db))]
+async fn delete_db_copy(
+    library_id: i64,
+    book_id: i64,
+    copy_id: i64,
+    db: &Database,
+) -> crate::Result<()> {
+    match sqlx::query(
+        "
+        delete from copies
+        where id = $1
+          and book_id = $2
+          and book_id in (
+            select id from books where library_id = $3
+          );
+        ",
+    )
+    .bind(copy_id)
+    .bind(book_id)
+    .bind(library_id)
+    .execute(db)
+    .await
+    .map_err(Error::from)
+    .inspect_err(telemetry::error)?
+    .rows_affected()
+    {
+        0 => Err(Error::NotFound),
+        1 => Ok(()),
+        _ => unreachable!(),
+    }
+    .inspect_err(telemetry::debug)
+}
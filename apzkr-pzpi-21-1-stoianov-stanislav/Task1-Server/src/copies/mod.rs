@@ -0,0 +1,59 @@
+mod barcode;
+mod condition;
+mod shelf_location;
+
+mod add;
+mod delete;
+mod update;
+mod view;
+
+pub use add::{add_copy, insert_default_copy};
+pub use delete::delete_copy;
+pub use update::update_copy;
+pub use view::list_copies;
+
+use serde::{Deserialize, Serialize};
+use serde_with::{serde_as, NoneAsEmptyString};
+
+use crate::id::{tag, Id};
+
+pub use self::{barcode::Barcode, condition::Condition};
+
+use self::{
+    barcode::UnvalidatedBarcode,
+    shelf_location::{ShelfLocation, UnvalidatedShelfLocation},
+};
+
+pub type CopyId = Id<{ tag("copy") }>;
+
+#[serde_as]
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NewCopy {
+    #[serde_as(as = "NoneAsEmptyString")]
+    #[serde(default)]
+    pub barcode: Option<UnvalidatedBarcode>,
+    #[serde(default)]
+    pub shelf_location: UnvalidatedShelfLocation,
+    #[serde(default)]
+    pub condition: Condition,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Copy {
+    pub id: CopyId,
+    pub barcode: Barcode,
+    pub shelf_location: ShelfLocation,
+    pub condition: Condition,
+    pub available: bool,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateCopy {
+    pub barcode: UnvalidatedBarcode,
+    #[serde(default)]
+    pub shelf_location: UnvalidatedShelfLocation,
+    pub condition: Condition,
+}
@@ -0,0 +1,34 @@
+use serde::Serialize;
+
+use crate::Error;
+
+pub type UnvalidatedShelfLocation = String;
+
+#[derive(Clone, Debug, Default, sqlx::Type, Serialize)]
+#[sqlx(transparent)]
+#[serde(transparent)]
+pub struct ShelfLocation(UnvalidatedShelfLocation);
+
+impl ShelfLocation {
+    pub fn new(location: UnvalidatedShelfLocation) -> crate::Result<Self> {
+        if location.len() > 50 {
+            Err(Error::Validation("shelf location is too long"))
+        } else {
+            Ok(Self(location))
+        }
+    }
+}
+
+impl TryFrom<UnvalidatedShelfLocation> for ShelfLocation {
+    type Error = Error;
+
+    fn try_from(value: UnvalidatedShelfLocation) -> Result<Self, Self::Error> {
+        Self::new(value)
+    }
+}
+
+impl From<ShelfLocation> for UnvalidatedShelfLocation {
+    fn from(value: ShelfLocation) -> Self {
+        value.0
+    }
+}
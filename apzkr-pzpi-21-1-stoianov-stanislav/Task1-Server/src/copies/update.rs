@@ -0,0 +1,100 @@
+use sqlx::error::ErrorKind;
+
+use crate::{
+    auth::UserId,
+    books::{check_owns, BookId},
+    database::{error_kind, Database},
+    libraries::LibraryId,
+    state::AppState,
+    telemetry, Error,
+};
+
+use super::{
+    barcode::Barcode, condition::Condition, shelf_location::ShelfLocation,
+    CopyId, UpdateCopy,
+};
+
+#[tracing::instrument(skip(state))]
+pub async fn update_copy(
+    owner_id: UserId,
+    library_id: LibraryId,
+    book_id: BookId,
+    copy_id: CopyId,
+    copy: UpdateCopy,
+    state: AppState,
+) -> crate::Result<()> {
+    let owner_id = owner_id
+        .sql_id(&state.id_cipher)
+        .map_err(|_| Error::LoggedOff)
+        .inspect_err(telemetry::debug)?;
+    let library_id = library_id
+        .sql_id(&state.id_cipher)
+        .map_err(|_| Error::NotFound)
+        .inspect_err(telemetry::debug)?;
+    check_owns(owner_id, library_id, &state.database).await?;
+    let book_id = book_id
+        .sql_id(&state.id_cipher)
+        .map_err(|_| Error::NotFound)
+        .inspect_err(telemetry::debug)?;
+    let copy_id = copy_id
+        .sql_id(&state.id_cipher)
+        .map_err(|_| Error::NotFound)
+        .inspect_err(telemetry::debug)?;
+    let copy = DbCopy {
+        id: copy_id,
+        library_id,
+        book_id,
+        barcode: Barcode::for_copy(copy.barcode, copy_id)?,
+        shelf_location: ShelfLocation::new(copy.shelf_location)?,
+        condition: copy.condition,
+    };
+    update_db_copy(&copy, &state.database).await
+}
+
+#[derive(Clone, Debug)]
+struct DbCopy {
+    id: i64,
+    library_id: i64,
+    book_id: i64,
+    barcode: Barcode,
+    shelf_location: ShelfLocation,
+    condition: Condition,
+}
+
+#[tracing::instrument(skip(db))]
+async fn update_db_copy(copy: &DbCopy, db: &Database) -> crate::Result<()> {
+    match sqlx::query(
+        "
+        update copies
+        set (barcode, shelf_location, condition) = ($1, $2, $3)
+        where id = $4
+          and book_id = $5
+          and book_id in (
+            select id from books where library_id = $6
+          );
+        ",
+    )
+    .bind(&copy.barcode)
+    .bind(&copy.shelf_location)
+    .bind(copy.condition)
+    .bind(copy.id)
+    .bind(copy.book_id)
+    .bind(copy.library_id)
+    .execute(db)
+    .await
+    {
+        Err(e) if error_kind(&e) == Some(ErrorKind::UniqueViolation) => {
+            Err(Error::BarcodeTaken).inspect_err(telemetry::debug)
+        }
+        other => match other
+            .map_err(Error::from)
+            .inspect_err(telemetry::error)?
+            .rows_affected()
+        {
+            0 => Err(Error::NotFound),
+            1 => Ok(()),
+            _ => unreachable!(),
+        }
+        .inspect_err(telemetry::debug),
+    }
+}
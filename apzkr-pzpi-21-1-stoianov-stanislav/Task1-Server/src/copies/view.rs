@@ -0,0 +1,76 @@
+use crate::{
+    books::BookId, database::Database, libraries::LibraryId, state::AppState,
+    telemetry, Error,
+};
+
+use super::{
+    barcode::Barcode, condition::Condition, shelf_location::ShelfLocation,
+    Copy, CopyId,
+};
+
+#[tracing::instrument(skip(state))]
+pub async fn list_copies(
+    library_id: LibraryId,
+    book_id: BookId,
+    state: AppState,
+) -> crate::Result<Vec<Copy>> {
+    let library_id = library_id
+        .sql_id(&state.id_cipher)
+        .map_err(|_| Error::NotFound)
+        .inspect_err(telemetry::debug)?;
+    let book_id = book_id
+        .sql_id(&state.id_cipher)
+        .map_err(|_| Error::NotFound)
+        .inspect_err(telemetry::debug)?;
+    get_book_copies(library_id, book_id, &state.database)
+        .await
+        .map(|copies| {
+            copies
+                .into_iter()
+                .map(|copy| Copy {
+                    id: CopyId::new(copy.id, &state.id_cipher),
+                    barcode: copy.barcode,
+                    shelf_location: copy.shelf_location,
+                    condition: copy.condition,
+                    available: copy.available,
+                })
+                .collect()
+        })
+}
+
+#[derive(Clone, Debug, sqlx::FromRow)]
+struct DbCopy {
+    id: i64,
+    barcode: Barcode,
+    shelf_location: ShelfLocation,
+    condition: Condition,
+    available: bool,
+}
+
+#[tracing::instrument(skip(db), err(Debug))]
+async fn get_book_copies(
+    library_id: i64,
+    book_id: i64,
+    db: &Database,
+) -> crate::Result<Vec<DbCopy>> {
+    sqlx::query_as(
+        "
+        select c.id, c.barcode, c.shelf_location, c.condition,
+          not exists (
+            select from lendings l
+            where l.copy_id = c.id
+              and l.returned_on is null
+          ) as available
+        from copies c
+        join books b on b.id = c.book_id
+        where c.book_id = $1
+          and b.library_id = $2
+        order by c.id;
+        ",
+    )
+    .bind(book_id)
+    .bind(library_id)
+    .fetch_all(db)
+    .await
+    .map_err(Error::from)
+}
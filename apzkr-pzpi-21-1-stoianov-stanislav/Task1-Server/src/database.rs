@@ -9,6 +9,8 @@ use crate::config::DatabaseConfig;
 
 pub type Database = Pool<Postgres>;
 
+pub type Transaction<'a> = sqlx::Transaction<'a, Postgres>;
+
 pub fn connect(config: DatabaseConfig) -> Database {
     let connect_options = PgConnectOptions::new()
         .host(&config.host)
@@ -27,3 +29,9 @@ pub fn connect(config: DatabaseConfig) -> Database {
 pub fn error_kind(error: &sqlx::Error) -> Option<ErrorKind> {
     error.as_database_error().map(DatabaseError::kind)
 }
+
+pub fn error_constraint(error: &sqlx::Error) -> Option<&str> {
+    error
+        .as_database_error()
+        .and_then(DatabaseError::constraint)
+}
@@ -0,0 +1,66 @@
+use crate::{
+    auth::{check_permission, Role, UserId},
+    books::check_owns,
+    database::Database,
+    state::AppState,
+    telemetry, Error,
+};
+
+use super::DeviceId;
+
+pub async fn check_manages(
+    user_id: UserId,
+    library_id: i64,
+    state: &AppState,
+) -> crate::Result<()> {
+    let db_user_id = user_id
+        .sql_id(&state.id_cipher)
+        .map_err(|_| Error::LoggedOff)
+        .inspect_err(telemetry::debug)?;
+    match check_owns(db_user_id, library_id, &state.database).await {
+        Err(Error::Unauthorized) => {
+            check_permission(user_id, state, |role| {
+                matches!(role, Role::Administrator)
+            })
+            .await
+        }
+        other => other,
+    }
+    .inspect_err(telemetry::debug)
+}
+
+#[tracing::instrument(skip(state))]
+pub async fn check_device(
+    device_id: DeviceId,
+    library_id: i64,
+    state: &AppState,
+) -> crate::Result<i64> {
+    let device_id = device_id
+        .sql_id(&state.id_cipher)
+        .map_err(|_| Error::InvalidApiKey)
+        .inspect_err(telemetry::debug)?;
+    get_device_library(device_id, &state.database)
+        .await?
+        .filter(|(device_library_id,)| *device_library_id == library_id)
+        .map(|_| device_id)
+        .ok_or(Error::Unauthorized)
+        .inspect_err(telemetry::debug)
+}
+
+#[tracing::instrument(skip(db), err(Debug))]
+async fn get_device_library(
+    device_id: i64,
+    db: &Database,
+) -> crate::Result<Option<(i64,)>> {
+    sqlx::query_as(
+        "
+        select library_id
+        from devices
+        where id = $1;
+        ",
+    )
+    .bind(device_id)
+    .fetch_optional(db)
+    .await
+    .map_err(Error::from)
+}
@@ -0,0 +1,53 @@
+use core::fmt;
+
+use argon2::password_hash::rand_core::{OsRng, RngCore};
+
+use crate::Error;
+
+use super::DeviceId;
+
+const SECRET_LEN: usize = 32;
+
+pub struct Secret(String);
+
+impl Secret {
+    pub fn generate() -> Self {
+        let mut bytes = [0; SECRET_LEN];
+        OsRng.fill_bytes(&mut bytes);
+        Self(bytes.iter().map(|byte| format!("{byte:02x}")).collect())
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        self.0.as_bytes()
+    }
+}
+
+pub struct ApiKey {
+    pub device_id: DeviceId,
+    pub secret: Secret,
+}
+
+impl ApiKey {
+    pub fn parse(key: &str) -> crate::Result<Self> {
+        key.split_once('.')
+            .and_then(|(device_id, secret)| {
+                Some(Self {
+                    device_id: device_id.parse().ok()?,
+                    secret: Secret(secret.to_owned()),
+                })
+            })
+            .ok_or(Error::InvalidApiKey)
+    }
+}
+
+impl fmt::Display for ApiKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}", self.device_id, self.secret.0)
+    }
+}
+
+impl fmt::Debug for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Secret(...)")
+    }
+}
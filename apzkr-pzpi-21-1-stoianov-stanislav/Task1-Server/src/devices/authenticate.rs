@@ -0,0 +1,72 @@
+use crate::{
+    auth::{verify_secret, PasswordHash},
+    database::Database,
+    state::AppState,
+    telemetry, Error,
+};
+
+use super::{api_key::ApiKey, DeviceId};
+
+#[tracing::instrument(skip_all)]
+pub async fn authenticate_device(
+    api_key: &str,
+    state: &AppState,
+) -> crate::Result<DeviceId> {
+    let api_key = ApiKey::parse(api_key).inspect_err(telemetry::debug)?;
+    let device_id = api_key
+        .device_id
+        .sql_id(&state.id_cipher)
+        .map_err(|_| Error::InvalidApiKey)
+        .inspect_err(telemetry::debug)?;
+    let key_hash = get_key_hash(device_id, &state.database).await?;
+    let hasher_config = state.hasher_config.clone();
+    telemetry::instrument_blocking(move || {
+        verify_secret(
+            api_key.secret.as_bytes(),
+            key_hash.as_ref().map(|(hash,)| hash),
+            (*hasher_config).clone(),
+        )
+    })
+    .await?
+    .map_err(|e| match e {
+        Error::InvalidCredentials => Error::InvalidApiKey,
+        e => e,
+    })
+    .inspect_err(telemetry::debug)?;
+    touch_device(device_id, &state.database).await?;
+    Ok(api_key.device_id)
+}
+
+#[tracing::instrument(skip(db), err(Debug))]
+async fn get_key_hash(
+    device_id: i64,
+    db: &Database,
+) -> crate::Result<Option<(PasswordHash,)>> {
+    sqlx::query_as(
+        "
+        select key_hash
+        from devices
+        where id = $1;
+        ",
+    )
+    .bind(device_id)
+    .fetch_optional(db)
+    .await
+    .map_err(Error::from)
+}
+
+#[tracing::instrument(skip(db), err(Debug))]
+async fn touch_device(device_id: i64, db: &Database) -> crate::Result<()> {
+    sqlx::query(
+        "
+        update devices
+        set last_used_at = now()
+        where id = $1;
+        ",
+    )
+    .bind(device_id)
+    .execute(db)
+    .await
+    .map(|_| ())
+    .map_err(Error::from)
+}
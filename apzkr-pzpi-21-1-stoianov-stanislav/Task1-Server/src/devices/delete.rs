@@ -0,0 +1,53 @@
+use crate::{
+    auth::UserId, database::Database, libraries::LibraryId, state::AppState,
+    telemetry, Error,
+};
+
+use super::{access::check_manages, DeviceId};
+
+#[tracing::instrument(skip(state))]
+pub async fn delete_device(
+    user_id: UserId,
+    library_id: LibraryId,
+    device_id: DeviceId,
+    state: AppState,
+) -> crate::Result<()> {
+    let library_id = library_id
+        .sql_id(&state.id_cipher)
+        .map_err(|_| Error::NotFound)
+        .inspect_err(telemetry::debug)?;
+    check_manages(user_id, library_id, &state).await?;
+    let device_id = device_id
+        .sql_id(&state.id_cipher)
+        .map_err(|_| Error::NotFound)
+        .inspect_err(telemetry::debug)?;
+    delete_db_device(library_id, device_id, &state.database).await
+}
+
+#[tracing::instrument(skip(db))]
+async fn delete_db_device(
+    library_id: i64,
+    device_id: i64,
+    db: &Database,
+) -> crate::Result<()> {
+    match sqlx::query(
+        "
+        delete from devices
+        where id = $1
+          and library_id = $2;
+        ",
+    )
+    .bind(device_id)
+    .bind(library_id)
+    .execute(db)
+    .await
+    .map_err(Error::from)
+    .inspect_err(telemetry::error)?
+    .rows_affected()
+    {
+        0 => Err(Error::NotFound),
+        1 => Ok(()),
+        _ => unreachable!(),
+    }
+    .inspect_err(telemetry::debug)
+}
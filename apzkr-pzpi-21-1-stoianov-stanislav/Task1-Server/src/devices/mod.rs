@@ -0,0 +1,48 @@
+mod api_key;
+mod name;
+
+mod access;
+mod authenticate;
+mod delete;
+mod register;
+mod view;
+
+pub use access::check_device;
+pub use authenticate::authenticate_device;
+pub use delete::delete_device;
+pub use register::register_device;
+pub use view::list_devices;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::id::{tag, Id};
+
+pub use self::name::Name;
+
+use self::name::UnvalidatedName;
+
+pub type DeviceId = Id<{ tag("device") }>;
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NewDevice {
+    pub name: UnvalidatedName,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Device {
+    pub id: DeviceId,
+    pub name: Name,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RegisteredDevice {
+    pub id: DeviceId,
+    pub name: Name,
+    pub api_key: String,
+}
@@ -0,0 +1,68 @@
+use crate::{
+    auth::{hash_secret, PasswordHash, UserId},
+    database::Database,
+    libraries::LibraryId,
+    state::AppState,
+    telemetry, Error,
+};
+
+use super::{
+    access::check_manages,
+    api_key::{ApiKey, Secret},
+    name::Name,
+    DeviceId, NewDevice, RegisteredDevice,
+};
+
+#[tracing::instrument(skip(state))]
+pub async fn register_device(
+    user_id: UserId,
+    library_id: LibraryId,
+    device: NewDevice,
+    state: AppState,
+) -> crate::Result<RegisteredDevice> {
+    let library_id = library_id
+        .sql_id(&state.id_cipher)
+        .map_err(|_| Error::NotFound)
+        .inspect_err(telemetry::debug)?;
+    check_manages(user_id, library_id, &state).await?;
+    let name = Name::new(device.name)?;
+    let secret = Secret::generate();
+    let (secret, key_hash) = telemetry::instrument_blocking(move || {
+        hash_secret(secret.as_bytes(), (*state.hasher_config).clone())
+            .map(|key_hash| (secret, key_hash))
+    })
+    .await??;
+    let id =
+        insert_device(library_id, &name, &key_hash, &state.database).await?;
+    let device_id = DeviceId::new(id, &state.id_cipher);
+    Ok(RegisteredDevice {
+        id: device_id,
+        name,
+        api_key: ApiKey { device_id, secret }.to_string(),
+    })
+}
+
+#[tracing::instrument(skip(db), err(Debug))]
+async fn insert_device(
+    library_id: i64,
+    name: &Name,
+    key_hash: &PasswordHash,
+    db: &Database,
+) -> crate::Result<i64> {
+    sqlx::query_as(
+        "
+        insert into devices
+          (library_id, name, key_hash, created_at)
+        values
+          ($1, $2, $3, now())
+        returning id;
+        ",
+    )
+    .bind(library_id)
+    .bind(name)
+    .bind(key_hash)
+    .fetch_one(db)
+    .await
+    .map(|(id,)| id)
+    .map_err(Error::from)
+}
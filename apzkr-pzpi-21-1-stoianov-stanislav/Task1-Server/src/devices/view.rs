@@ -0,0 +1,61 @@
+use chrono::{DateTime, Utc};
+
+use crate::{
+    auth::UserId, database::Database, libraries::LibraryId, state::AppState,
+    telemetry, Error,
+};
+
+use super::{access::check_manages, name::Name, Device, DeviceId};
+
+#[tracing::instrument(skip(state))]
+pub async fn list_devices(
+    user_id: UserId,
+    library_id: LibraryId,
+    state: AppState,
+) -> crate::Result<Vec<Device>> {
+    let library_id = library_id
+        .sql_id(&state.id_cipher)
+        .map_err(|_| Error::NotFound)
+        .inspect_err(telemetry::debug)?;
+    check_manages(user_id, library_id, &state).await?;
+    get_devices(library_id, &state.database)
+        .await
+        .map(|devices| {
+            devices
+                .into_iter()
+                .map(|device| Device {
+                    id: DeviceId::new(device.id, &state.id_cipher),
+                    name: device.name,
+                    created_at: device.created_at,
+                    last_used_at: device.last_used_at,
+                })
+                .collect()
+        })
+}
+
+#[derive(Clone, Debug, sqlx::FromRow)]
+struct DbDevice {
+    id: i64,
+    name: Name,
+    created_at: DateTime<Utc>,
+    last_used_at: Option<DateTime<Utc>>,
+}
+
+#[tracing::instrument(skip(db), err(Debug))]
+async fn get_devices(
+    library_id: i64,
+    db: &Database,
+) -> crate::Result<Vec<DbDevice>> {
+    sqlx::query_as(
+        "
+        select id, name, created_at, last_used_at
+        from devices
+        where library_id = $1
+        order by created_at, id;
+        ",
+    )
+    .bind(library_id)
+    .fetch_all(db)
+    .await
+    .map_err(Error::from)
+}
@@ -14,8 +14,28 @@ pub enum Error {
     LoggedOff,
     #[error("wrong email or password")]
     InvalidCredentials,
+    #[error("device api key is invalid")]
+    InvalidApiKey,
     #[error("requested resource not found")]
     NotFound,
+    #[error("book is already lent")]
+    AlreadyLent,
+    #[error("book with this isbn already exists in the library")]
+    IsbnTaken,
+    #[error("barcode is already in use")]
+    BarcodeTaken,
+    #[error("book is reserved for another reader")]
+    Reserved,
+    #[error("book is already on hold")]
+    HoldExists,
+    #[error("lending cannot be renewed: {0}")]
+    NotRenewable(&'static str),
+    #[error("request with this idempotency key is still in progress")]
+    RequestInProgress,
+    #[error("request body is too large")]
+    PayloadTooLarge,
+    #[error("outstanding balance exceeds the library limit")]
+    BalanceLimitExceeded,
     #[error("no permission for the resourse")]
     Unauthorized,
     #[error("an unexpected error occurred")]
@@ -0,0 +1,153 @@
+use anyhow::Context;
+use futures_util::{stream::BoxStream, StreamExt};
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+
+use crate::{database::Database, telemetry};
+
+const BUFFERED_ROWS: usize = 64;
+
+#[derive(Clone, Copy, Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ExportFormat {
+    #[default]
+    Csv,
+    Jsonl,
+}
+
+impl ExportFormat {
+    pub fn content_type(self) -> &'static str {
+        match self {
+            Self::Csv => "text/csv; charset=utf-8",
+            Self::Jsonl => "application/x-ndjson",
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Csv => "csv",
+            Self::Jsonl => "jsonl",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportOptions {
+    #[serde(default)]
+    pub format: ExportFormat,
+}
+
+#[derive(Debug)]
+pub struct Export {
+    pub content_type: &'static str,
+    pub file_name: String,
+    pub chunks: mpsc::Receiver<crate::Result<Vec<u8>>>,
+}
+
+pub trait Encoder<T>: Send {
+    fn header(&mut self) -> Vec<u8> {
+        Vec::new()
+    }
+
+    fn encode(&mut self, row: T) -> crate::Result<Vec<u8>>;
+
+    fn footer(&mut self) -> Vec<u8> {
+        Vec::new()
+    }
+}
+
+pub fn export<Q, R, T, F>(
+    content_type: &'static str,
+    file_name: String,
+    db: Database,
+    query: Q,
+    into_row: F,
+    mut encoder: Box<dyn Encoder<T>>,
+) -> Export
+where
+    Q: for<'a> FnOnce(&'a Database) -> BoxStream<'a, sqlx::Result<R>>
+        + Send
+        + 'static,
+    R: Send,
+    T: 'static,
+    F: Fn(R) -> T + Send + 'static,
+{
+    let (sender, chunks) = mpsc::channel(BUFFERED_ROWS);
+    tokio::spawn(async move {
+        let header = encoder.header();
+        if !header.is_empty() && sender.send(Ok(header)).await.is_err() {
+            return;
+        }
+        let mut rows = query(&db);
+        while let Some(row) = rows.next().await {
+            let chunk = row
+                .map_err(crate::Error::from)
+                .and_then(|row| encoder.encode(into_row(row)))
+                .inspect_err(telemetry::error);
+            let failed = chunk.is_err();
+            if sender.send(chunk).await.is_err() || failed {
+                return;
+            }
+        }
+        let footer = encoder.footer();
+        if !footer.is_empty() {
+            let _ = sender.send(Ok(footer)).await;
+        }
+    });
+    Export {
+        content_type,
+        file_name,
+        chunks,
+    }
+}
+
+/// Column names of an exported row, written as the CSV header even when
+/// there are no rows.
+pub trait Columns {
+    const COLUMNS: &'static [&'static str];
+}
+
+pub enum Serialized {
+    Csv,
+    Jsonl,
+}
+
+impl Serialized {
+    pub fn new(format: ExportFormat) -> Self {
+        match format {
+            ExportFormat::Csv => Self::Csv,
+            ExportFormat::Jsonl => Self::Jsonl,
+        }
+    }
+}
+
+impl<T: Serialize + Columns> Encoder<T> for Serialized {
+    fn header(&mut self) -> Vec<u8> {
+        match self {
+            Self::Csv => format!("{}\n", T::COLUMNS.join(",")).into_bytes(),
+            Self::Jsonl => Vec::new(),
+        }
+    }
+
+    fn encode(&mut self, row: T) -> crate::Result<Vec<u8>> {
+        match self {
+            Self::Csv => {
+                let mut writer = csv::WriterBuilder::new()
+                    .has_headers(false)
+                    .from_writer(Vec::new());
+                writer.serialize(row).context("serialize csv row")?;
+                writer
+                    .into_inner()
+                    .context("flush csv row")
+                    .map_err(crate::Error::from)
+            }
+            Self::Jsonl => {
+                let mut line =
+                    serde_json::to_vec(&row).context("serialize json row")?;
+                line.push(b'\n');
+                Ok(line)
+            }
+        }
+    }
+}
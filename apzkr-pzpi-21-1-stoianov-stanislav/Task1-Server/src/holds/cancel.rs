@@ -0,0 +1,52 @@
+use crate::{
+    auth::UserId, database::Transaction, state::AppState, telemetry, Error,
+};
+
+use super::{pickup::promote_next_hold, HoldId};
+
+#[tracing::instrument(skip(state))]
+pub async fn cancel_hold(
+    user_id: UserId,
+    hold_id: HoldId,
+    state: AppState,
+) -> crate::Result<()> {
+    let user_id = user_id
+        .sql_id(&state.id_cipher)
+        .map_err(|_| Error::LoggedOff)
+        .inspect_err(telemetry::debug)?;
+    let hold_id = hold_id
+        .sql_id(&state.id_cipher)
+        .map_err(|_| Error::NotFound)
+        .inspect_err(telemetry::debug)?;
+    let mut tx = state.database.begin().await?;
+    let book_id = set_cancelled(user_id, hold_id, &mut tx)
+        .await?
+        .ok_or(Error::NotFound)
+        .inspect_err(telemetry::debug)?;
+    promote_next_hold(book_id, &mut tx).await?;
+    tx.commit().await.map_err(Error::from)
+}
+
+#[tracing::instrument(skip(tx), err(Debug))]
+async fn set_cancelled(
+    user_id: i64,
+    hold_id: i64,
+    tx: &mut Transaction<'_>,
+) -> crate::Result<Option<i64>> {
+    sqlx::query_as::<_, (_,)>(
+        "
+        update holds
+        set status = 'cancelled'
+        where id = $1
+          and user_id = $2
+          and status in ('waiting', 'ready')
+        returning book_id;
+        ",
+    )
+    .bind(hold_id)
+    .bind(user_id)
+    .fetch_optional(&mut **tx)
+    .await
+    .map(|maybe| maybe.map(|id| id.0))
+    .map_err(Error::from)
+}
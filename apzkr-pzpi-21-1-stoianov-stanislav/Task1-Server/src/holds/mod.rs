@@ -0,0 +1,55 @@
+mod status;
+
+mod cancel;
+mod pickup;
+mod place;
+mod queue;
+
+pub use cancel::cancel_hold;
+pub use pickup::{claim_hold, promote_next_hold};
+pub use place::place_hold;
+pub use queue::{hold_queue, my_holds};
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::Serialize;
+
+use crate::{
+    auth::User,
+    books::Book,
+    id::{tag, Id},
+    libraries::{self, LibraryId},
+};
+
+pub use self::status::HoldStatus;
+
+pub type HoldId = Id<{ tag("hold") }>;
+
+const PICKUP_WINDOW_DAYS: i32 = 3;
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Hold {
+    pub id: HoldId,
+    pub book: Book,
+    pub library_id: LibraryId,
+    pub library_name: libraries::Name,
+    pub status: HoldStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub position: Option<i64>,
+    pub placed_at: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ready_until: Option<NaiveDate>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueuedHold {
+    pub id: HoldId,
+    pub book: Book,
+    pub reader: User,
+    pub status: HoldStatus,
+    pub position: i64,
+    pub placed_at: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ready_until: Option<NaiveDate>,
+}
@@ -0,0 +1,148 @@
+use crate::{database::Transaction, telemetry, Error};
+
+use super::PICKUP_WINDOW_DAYS;
+
+#[tracing::instrument(skip(tx), err(Debug))]
+pub async fn promote_next_hold(
+    book_id: i64,
+    tx: &mut Transaction<'_>,
+) -> crate::Result<()> {
+    expire_holds(book_id, tx).await?;
+    sqlx::query(
+        "
+        update holds
+        set (status, ready_until) = ('ready', current_date + $2)
+        where id in (
+          select id
+          from holds
+          where book_id = $1
+            and status = 'waiting'
+          order by placed_at, id
+          limit greatest(0, (
+            select count(*)
+            from copies c
+            where c.book_id = $1
+              and not exists (
+                select from lendings l
+                where l.copy_id = c.id
+                  and l.returned_on is null
+              )
+          ) - (
+            select count(*)
+            from holds
+            where book_id = $1
+              and status = 'ready'
+          ))
+        );
+        ",
+    )
+    .bind(book_id)
+    .bind(PICKUP_WINDOW_DAYS)
+    .execute(&mut **tx)
+    .await
+    .map(|_| ())
+    .map_err(Error::from)
+}
+
+#[tracing::instrument(skip(tx))]
+pub async fn claim_hold(
+    book_id: i64,
+    lendee_id: i64,
+    tx: &mut Transaction<'_>,
+) -> crate::Result<()> {
+    promote_next_hold(book_id, tx).await?;
+    let pickup = get_pickup(book_id, lendee_id, tx).await?;
+    match pickup.hold_id {
+        Some(hold_id) => fulfill_hold(hold_id, tx).await,
+        None if pickup.ready < pickup.available => Ok(()),
+        None => Err(Error::Reserved).inspect_err(telemetry::debug),
+    }
+}
+
+#[derive(Clone, Debug, sqlx::FromRow)]
+struct Pickup {
+    hold_id: Option<i64>,
+    ready: i64,
+    available: i64,
+}
+
+#[tracing::instrument(skip(tx), err(Debug))]
+async fn get_pickup(
+    book_id: i64,
+    lendee_id: i64,
+    tx: &mut Transaction<'_>,
+) -> crate::Result<Pickup> {
+    sqlx::query_as(
+        "
+        select
+          (
+            select id
+            from holds
+            where book_id = $1
+              and user_id = $2
+              and status = 'ready'
+          ) as hold_id,
+          (
+            select count(*)
+            from holds
+            where book_id = $1
+              and status = 'ready'
+          ) as ready,
+          (
+            select count(*)
+            from copies c
+            where c.book_id = $1
+              and not exists (
+                select from lendings l
+                where l.copy_id = c.id
+                  and l.returned_on is null
+              )
+          ) as available;
+        ",
+    )
+    .bind(book_id)
+    .bind(lendee_id)
+    .fetch_one(&mut **tx)
+    .await
+    .map_err(Error::from)
+}
+
+#[tracing::instrument(skip(tx), err(Debug))]
+async fn expire_holds(
+    book_id: i64,
+    tx: &mut Transaction<'_>,
+) -> crate::Result<()> {
+    sqlx::query(
+        "
+        update holds
+        set status = 'expired'
+        where book_id = $1
+          and status = 'ready'
+          and ready_until < current_date;
+        ",
+    )
+    .bind(book_id)
+    .execute(&mut **tx)
+    .await
+    .map(|_| ())
+    .map_err(Error::from)
+}
+
+#[tracing::instrument(skip(tx), err(Debug))]
+async fn fulfill_hold(
+    hold_id: i64,
+    tx: &mut Transaction<'_>,
+) -> crate::Result<()> {
+    sqlx::query(
+        "
+        update holds
+        set status = 'fulfilled'
+        where id = $1;
+        ",
+    )
+    .bind(hold_id)
+    .execute(&mut **tx)
+    .await
+    .map(|_| ())
+    .map_err(Error::from)
+}
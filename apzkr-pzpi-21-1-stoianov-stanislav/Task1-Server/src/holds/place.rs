@@ -0,0 +1,78 @@
+use sqlx::error::ErrorKind;
+
+use crate::{
+    auth::UserId,
+    books::BookId,
+    database::{error_kind, Transaction},
+    libraries::LibraryId,
+    state::AppState,
+    telemetry, Error,
+};
+
+use super::pickup::promote_next_hold;
+
+#[tracing::instrument(skip(state))]
+pub async fn place_hold(
+    user_id: UserId,
+    library_id: LibraryId,
+    book_id: BookId,
+    state: AppState,
+) -> crate::Result<()> {
+    let user_id = user_id
+        .sql_id(&state.id_cipher)
+        .map_err(|_| Error::LoggedOff)
+        .inspect_err(telemetry::debug)?;
+    let library_id = library_id
+        .sql_id(&state.id_cipher)
+        .map_err(|_| Error::NotFound)
+        .inspect_err(telemetry::debug)?;
+    let book_id = book_id
+        .sql_id(&state.id_cipher)
+        .map_err(|_| Error::NotFound)
+        .inspect_err(telemetry::debug)?;
+    let mut tx = state.database.begin().await?;
+    save_hold(user_id, library_id, book_id, &mut tx).await?;
+    promote_next_hold(book_id, &mut tx).await?;
+    tx.commit().await.map_err(Error::from)
+}
+
+#[tracing::instrument(skip(tx))]
+async fn save_hold(
+    user_id: i64,
+    library_id: i64,
+    book_id: i64,
+    tx: &mut Transaction<'_>,
+) -> crate::Result<()> {
+    match sqlx::query(
+        "
+        insert into holds
+          (book_id, user_id, status, placed_at)
+        select $1, $2, 'waiting', now()
+        where exists (
+          select from books
+          where id = $1
+            and library_id = $3
+        );
+        ",
+    )
+    .bind(book_id)
+    .bind(user_id)
+    .bind(library_id)
+    .execute(&mut **tx)
+    .await
+    {
+        Err(e) if error_kind(&e) == Some(ErrorKind::UniqueViolation) => {
+            Err(Error::HoldExists).inspect_err(telemetry::debug)
+        }
+        other => match other
+            .map_err(Error::from)
+            .inspect_err(telemetry::error)?
+            .rows_affected()
+        {
+            0 => Err(Error::NotFound),
+            1 => Ok(()),
+            _ => unreachable!(),
+        }
+        .inspect_err(telemetry::debug),
+    }
+}
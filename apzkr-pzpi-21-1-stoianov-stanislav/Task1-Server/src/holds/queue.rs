@@ -0,0 +1,194 @@
+use chrono::{DateTime, NaiveDate, Utc};
+
+use crate::{
+    auth::{Email, Name, User, UserId},
+    books::{self, check_owns, Author, Book, BookId, Genre, Isbn, Year},
+    database::Database,
+    libraries::{self, LibraryId},
+    state::AppState,
+    telemetry, Error,
+};
+
+use super::{Hold, HoldId, HoldStatus, QueuedHold};
+
+#[tracing::instrument(skip(state))]
+pub async fn my_holds(
+    user_id: UserId,
+    state: AppState,
+) -> crate::Result<Vec<Hold>> {
+    let user_id = user_id
+        .sql_id(&state.id_cipher)
+        .map_err(|_| Error::LoggedOff)
+        .inspect_err(telemetry::debug)?;
+    get_user_holds(user_id, &state.database).await.map(|holds| {
+        holds
+            .into_iter()
+            .map(|hold| Hold {
+                id: HoldId::new(hold.id, &state.id_cipher),
+                book: Book {
+                    id: BookId::new(hold.book_id, &state.id_cipher),
+                    year: hold.year,
+                    name: hold.book_name,
+                    genre: hold.genre,
+                    author: hold.author,
+                    isbn: hold.isbn,
+                },
+                library_id: LibraryId::new(hold.library_id, &state.id_cipher),
+                library_name: hold.library_name,
+                status: hold.status,
+                position: hold.position,
+                placed_at: hold.placed_at,
+                ready_until: hold.ready_until,
+            })
+            .collect()
+    })
+}
+
+#[tracing::instrument(skip(state))]
+pub async fn hold_queue(
+    owner_id: UserId,
+    library_id: LibraryId,
+    state: AppState,
+) -> crate::Result<Vec<QueuedHold>> {
+    let owner_id = owner_id
+        .sql_id(&state.id_cipher)
+        .map_err(|_| Error::LoggedOff)
+        .inspect_err(telemetry::debug)?;
+    let library_id = library_id
+        .sql_id(&state.id_cipher)
+        .map_err(|_| Error::NotFound)
+        .inspect_err(telemetry::debug)?;
+    check_owns(owner_id, library_id, &state.database).await?;
+    get_library_holds(library_id, &state.database)
+        .await
+        .map(|holds| {
+            holds
+                .into_iter()
+                .map(|hold| QueuedHold {
+                    id: HoldId::new(hold.id, &state.id_cipher),
+                    book: Book {
+                        id: BookId::new(hold.book_id, &state.id_cipher),
+                        year: hold.year,
+                        name: hold.book_name,
+                        genre: hold.genre,
+                        author: hold.author,
+                        isbn: hold.isbn,
+                    },
+                    reader: User {
+                        id: UserId::new(hold.user_id, &state.id_cipher),
+                        name: hold.user_name,
+                        email: hold.email,
+                    },
+                    status: hold.status,
+                    position: hold.position,
+                    placed_at: hold.placed_at,
+                    ready_until: hold.ready_until,
+                })
+                .collect()
+        })
+}
+
+#[derive(Clone, Debug, sqlx::FromRow)]
+struct DbHold {
+    id: i64,
+    status: HoldStatus,
+    position: Option<i64>,
+    placed_at: DateTime<Utc>,
+    ready_until: Option<NaiveDate>,
+    book_id: i64,
+    year: Year,
+    book_name: books::Name,
+    genre: Genre,
+    author: Author,
+    isbn: Option<Isbn>,
+    library_id: i64,
+    library_name: libraries::Name,
+}
+
+#[derive(Clone, Debug, sqlx::FromRow)]
+struct DbQueuedHold {
+    id: i64,
+    status: HoldStatus,
+    position: i64,
+    placed_at: DateTime<Utc>,
+    ready_until: Option<NaiveDate>,
+    book_id: i64,
+    year: Year,
+    book_name: books::Name,
+    genre: Genre,
+    author: Author,
+    isbn: Option<Isbn>,
+    user_id: i64,
+    user_name: Name,
+    email: Email,
+}
+
+#[tracing::instrument(skip(db), err(Debug))]
+async fn get_user_holds(
+    user_id: i64,
+    db: &Database,
+) -> crate::Result<Vec<DbHold>> {
+    sqlx::query_as(
+        "
+        with queue as (
+          select id, row_number() over (
+            partition by book_id
+            order by placed_at, id
+          ) as position
+          from holds
+          where status = 'waiting'
+        )
+        select h.id,
+          case
+            when h.status = 'ready' and h.ready_until < current_date
+              then 'expired'
+            else h.status
+          end::varchar as status,
+          q.position, h.placed_at, h.ready_until,
+          b.id as book_id, b.year, b.name as book_name, b.genre, b.author,
+          b.isbn,
+          lib.id as library_id, lib.name as library_name
+        from holds h
+        join books b on b.id = h.book_id
+        join libraries lib on lib.id = b.library_id
+        left join queue q on q.id = h.id
+        where h.user_id = $1
+        order by h.placed_at desc, h.id desc;
+        ",
+    )
+    .bind(user_id)
+    .fetch_all(db)
+    .await
+    .map_err(Error::from)
+}
+
+#[tracing::instrument(skip(db), err(Debug))]
+async fn get_library_holds(
+    library_id: i64,
+    db: &Database,
+) -> crate::Result<Vec<DbQueuedHold>> {
+    sqlx::query_as(
+        "
+        select h.id, h.status,
+          row_number() over (
+            partition by h.book_id
+            order by h.status = 'waiting', h.placed_at, h.id
+          ) as position,
+          h.placed_at, h.ready_until,
+          b.id as book_id, b.year, b.name as book_name, b.genre, b.author,
+          b.isbn,
+          u.id as user_id, u.name as user_name, u.email
+        from holds h
+        join books b on b.id = h.book_id
+        join users u on u.id = h.user_id
+        where b.library_id = $1
+          and (h.status = 'waiting'
+            or (h.status = 'ready' and h.ready_until >= current_date))
+        order by b.id, position;
+        ",
+    )
+    .bind(library_id)
+    .fetch_all(db)
+    .await
+    .map_err(Error::from)
+}
@@ -1,9 +1,13 @@
 use anyhow::Context;
 use axum::{
-    extract::{FromRequestParts, State},
-    http::{request::Parts, StatusCode},
-    response::{IntoResponse, Response},
-    routing::{get, post, put},
+    extract::{FromRequestParts, Path, Query, State},
+    http::{
+        header::{SET_COOKIE, USER_AGENT},
+        request::Parts,
+        HeaderMap, HeaderName, StatusCode,
+    },
+    response::{AppendHeaders, IntoResponse, Response},
+    routing::{delete, get, post, put},
     Form, Json, Router,
 };
 use axum_extra::extract::{
@@ -13,15 +17,18 @@ use axum_extra::extract::{
 
 use crate::{
     auth::{
-        get_all_users, get_user, parse_access_token, sign_in, sign_up,
-        update_user, TokenPair, UserId,
+        get_all_users, get_user, list_sessions, parse_access_token, refresh,
+        revoke_session, sign_in, sign_out, sign_out_all, sign_up, update_user,
+        TokenPair, UserAgent, UserId,
     },
+    ledger::user_balance,
     state::AppState,
     Error,
 };
 
 static ACCESS_TOKEN: &str = "access-token";
 static REFRESH_TOKEN: &str = "refresh-token";
+static LEGACY_REFRESH_PATH: &str = "/auth/refresh";
 
 pub fn router() -> Router<AppState> {
     Router::new()
@@ -35,8 +42,51 @@ pub fn router() -> Router<AppState> {
         )
         .route(
             "/sign-in",
-            post(|State(state), Form(credentials)| async move {
-                sign_in(credentials, state).await
+            post(|State(state), headers: HeaderMap, Form(credentials)| async move {
+                let user_agent = headers
+                    .get(USER_AGENT)
+                    .and_then(|value| value.to_str().ok())
+                    .map(UserAgent::new)
+                    .unwrap_or_default();
+                sign_in(credentials, user_agent, state).await
+            }),
+        )
+        .route(
+            "/refresh",
+            post(|State(state), cookies: CookieJar| async move {
+                let cookie =
+                    cookies.get(REFRESH_TOKEN).ok_or(Error::LoggedOff)?;
+                refresh(cookie.value(), state).await
+            }),
+        )
+        .route(
+            "/sign-out",
+            post(|State(state), cookies: CookieJar| async move {
+                let cookie =
+                    cookies.get(REFRESH_TOKEN).ok_or(Error::LoggedOff)?;
+                sign_out(cookie.value(), state)
+                    .await
+                    .map(|_| clear_tokens(cookies))
+            }),
+        )
+        .route(
+            "/sign-out-all",
+            post(|id: UserId, State(state), cookies: CookieJar| async move {
+                sign_out_all(id, state).await.map(|_| clear_tokens(cookies))
+            }),
+        )
+        .route(
+            "/sessions",
+            get(|id: UserId, State(state), cookies: CookieJar| async move {
+                let refresh_token =
+                    cookies.get(REFRESH_TOKEN).map(|cookie| cookie.value());
+                list_sessions(id, refresh_token, state).await.map(Json)
+            }),
+        )
+        .route(
+            "/sessions/:id",
+            delete(|id: UserId, Path(session_id), State(state)| async move {
+                revoke_session(id, session_id, state).await
             }),
         )
         .route(
@@ -51,10 +101,16 @@ pub fn router() -> Router<AppState> {
                 update_user(id, user_info, state).await
             }),
         )
+        .route(
+            "/me/balance",
+            get(|id: UserId, State(state)| async move {
+                user_balance(id, state).await.map(Json)
+            }),
+        )
         .route(
             "/users",
-            get(|admin_id: UserId, State(state)| async move {
-                get_all_users(admin_id, state).await.map(Json)
+            get(|admin_id: UserId, Query(page), State(state)| async move {
+                get_all_users(admin_id, page, state).await.map(Json)
             }),
         )
 }
@@ -87,11 +143,36 @@ impl IntoResponse for TokenPair {
         };
         let refresh = {
             let mut cookie = Cookie::new(REFRESH_TOKEN, self.refresh_token);
-            cookie.set_path("/auth/refresh");
+            cookie.set_path("/auth");
             cookie.set_same_site(SameSite::None);
             cookie.set_http_only(true);
             cookie
         };
-        CookieJar::new().add(access).add(refresh).into_response()
+        (
+            CookieJar::new().add(access).add(refresh),
+            expire_legacy_refresh(),
+        )
+            .into_response()
     }
 }
+
+fn clear_tokens(cookies: CookieJar) -> impl IntoResponse {
+    (
+        cookies
+            .remove(Cookie::build(ACCESS_TOKEN).path("/"))
+            .remove(Cookie::build(REFRESH_TOKEN).path("/auth")),
+        expire_legacy_refresh(),
+    )
+}
+
+/// Refresh tokens used to be scoped to `/auth/refresh`. The jar keys cookies
+/// by name only, so that one is expired with a separate header.
+fn expire_legacy_refresh() -> AppendHeaders<[(HeaderName, String); 1]> {
+    let mut cookie = Cookie::build(REFRESH_TOKEN)
+        .path(LEGACY_REFRESH_PATH)
+        .same_site(SameSite::None)
+        .http_only(true)
+        .build();
+    cookie.make_removal();
+    AppendHeaders([(SET_COOKIE, cookie.to_string())])
+}
@@ -0,0 +1,32 @@
+use axum::{
+    extract::{Path, Query, State},
+    routing::get,
+    Json, Router,
+};
+
+use crate::{
+    books::{find_by_isbn, lending_score, search_books},
+    state::AppState,
+};
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/search",
+            get(|Query(query), State(state)| async move {
+                search_books(query, state).await.map(Json)
+            }),
+        )
+        .route(
+            "/by-isbn/:isbn",
+            get(|Path(isbn), State(state)| async move {
+                find_by_isbn(isbn, state).await.map(Json)
+            }),
+        )
+        .route(
+            "/:id/lending-score",
+            get(|Path(book_id), State(state)| async move {
+                lending_score(book_id, state).await.map(Json)
+            }),
+        )
+}
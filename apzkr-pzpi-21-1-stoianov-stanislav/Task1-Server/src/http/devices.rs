@@ -0,0 +1,75 @@
+use axum::{
+    extract::{FromRequestParts, Path, State},
+    http::{request::Parts, StatusCode},
+    routing::{delete, get, post},
+    Form, Json, Router,
+};
+
+use crate::{
+    auth::UserId,
+    devices::{
+        authenticate_device, delete_device, list_devices, register_device,
+        DeviceId,
+    },
+    state::AppState,
+    Error,
+};
+
+pub static API_KEY: &str = "x-api-key";
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/",
+            post(
+                |user_id: UserId,
+                 Path(library_id),
+                 State(state),
+                 Form(device)| async move {
+                    register_device(user_id, library_id, device, state)
+                        .await
+                        .map(|device| (StatusCode::CREATED, Json(device)))
+                },
+            ),
+        )
+        .route(
+            "/",
+            get(
+                |user_id: UserId, Path(library_id), State(state)| async move {
+                    list_devices(user_id, library_id, state).await.map(Json)
+                },
+            ),
+        )
+        .route(
+            "/:id",
+            delete(
+                |user_id: UserId,
+                 Path((library_id, device_id)),
+                 State(state)| async move {
+                    delete_device(user_id, library_id, device_id, state).await
+                },
+            ),
+        )
+}
+
+#[axum::async_trait]
+impl FromRequestParts<AppState> for DeviceId {
+    type Rejection = Error;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &AppState,
+    ) -> Result<Self, Self::Rejection> {
+        if let Some(&device_id) = parts.extensions.get::<DeviceId>() {
+            return Ok(device_id);
+        }
+        let api_key = parts
+            .headers
+            .get(API_KEY)
+            .and_then(|value| value.to_str().ok())
+            .ok_or(Error::InvalidApiKey)?;
+        let device_id = authenticate_device(api_key, state).await?;
+        parts.extensions.insert(device_id);
+        Ok(device_id)
+    }
+}
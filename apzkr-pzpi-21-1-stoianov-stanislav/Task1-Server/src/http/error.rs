@@ -15,12 +15,21 @@ impl IntoResponse for crate::Error {
         use crate::Error;
         let code = match self {
             Error::Validation(_) => StatusCode::UNPROCESSABLE_ENTITY,
-            Error::AccountExists => StatusCode::CONFLICT,
-            Error::LoggedOff | Error::InvalidCredentials => {
-                StatusCode::UNAUTHORIZED
-            }
+            Error::AccountExists
+            | Error::AlreadyLent
+            | Error::IsbnTaken
+            | Error::BarcodeTaken
+            | Error::Reserved
+            | Error::HoldExists
+            | Error::NotRenewable(_)
+            | Error::RequestInProgress => StatusCode::CONFLICT,
+            Error::LoggedOff
+            | Error::InvalidCredentials
+            | Error::InvalidApiKey => StatusCode::UNAUTHORIZED,
             Error::Unauthorized => StatusCode::FORBIDDEN,
             Error::NotFound => StatusCode::NOT_FOUND,
+            Error::BalanceLimitExceeded => StatusCode::PAYMENT_REQUIRED,
+            Error::PayloadTooLarge => StatusCode::PAYLOAD_TOO_LARGE,
             Error::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
         };
         let message = ErrorMessage {
@@ -0,0 +1,26 @@
+use axum::{
+    body::Body,
+    http::header::{CONTENT_DISPOSITION, CONTENT_TYPE},
+    response::{IntoResponse, Response},
+};
+use futures_util::stream;
+
+use crate::export::Export;
+
+impl IntoResponse for Export {
+    fn into_response(self) -> Response {
+        let disposition =
+            format!("attachment; filename=\"{}\"", self.file_name);
+        let chunks = stream::unfold(self.chunks, |mut chunks| async move {
+            chunks.recv().await.map(|chunk| (chunk, chunks))
+        });
+        (
+            [
+                (CONTENT_TYPE, self.content_type.to_owned()),
+                (CONTENT_DISPOSITION, disposition),
+            ],
+            Body::from_stream(chunks),
+        )
+            .into_response()
+    }
+}
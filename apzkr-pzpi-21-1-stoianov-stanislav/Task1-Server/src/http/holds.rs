@@ -0,0 +1,27 @@
+use axum::{
+    extract::{Path, State},
+    routing::{delete, get},
+    Json, Router,
+};
+
+use crate::{
+    auth::UserId,
+    holds::{cancel_hold, my_holds},
+    state::AppState,
+};
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/my",
+            get(|user_id: UserId, State(state)| async move {
+                my_holds(user_id, state).await.map(Json)
+            }),
+        )
+        .route(
+            "/:id",
+            delete(|user_id: UserId, Path(id), State(state)| async move {
+                cancel_hold(user_id, id, state).await
+            }),
+        )
+}
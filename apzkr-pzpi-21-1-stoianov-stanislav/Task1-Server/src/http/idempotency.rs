@@ -0,0 +1,119 @@
+use anyhow::Context;
+use axum::{
+    body::{to_bytes, Body},
+    extract::{FromRequestParts, Request, State},
+    http::{
+        header::CONTENT_TYPE, request::Parts, HeaderValue, Method, StatusCode,
+    },
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+use crate::{
+    auth::UserId,
+    devices::DeviceId,
+    idempotency::{
+        claim_key, Caller, Claim, Fingerprint, IdempotencyKey, StoredResponse,
+    },
+    state::AppState,
+    telemetry, Error,
+};
+
+use super::devices::API_KEY;
+
+const IDEMPOTENCY_KEY: &str = "idempotency-key";
+const IDEMPOTENT_REPLAYED: &str = "idempotent-replayed";
+const MAX_BODY_SIZE: usize = 16 * 1024 * 1024;
+
+pub async fn idempotency(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    handle(state, request, next)
+        .await
+        .unwrap_or_else(IntoResponse::into_response)
+}
+
+async fn handle(
+    state: AppState,
+    request: Request,
+    next: Next,
+) -> crate::Result<Response> {
+    let key = match request.headers().get(IDEMPOTENCY_KEY) {
+        Some(key) if request.method() == Method::POST => key,
+        _ => return Ok(next.run(request).await),
+    };
+    let key = key
+        .to_str()
+        .map_err(|_| Error::Validation("idempotency key must be ascii"))
+        .map(str::to_owned)
+        .and_then(IdempotencyKey::new)
+        .inspect_err(telemetry::debug)?;
+    let (mut parts, body) = request.into_parts();
+    let Some(caller) = resolve_caller(&mut parts, &state).await else {
+        return Ok(next.run(Request::from_parts(parts, body)).await);
+    };
+    let body = to_bytes(body, MAX_BODY_SIZE)
+        .await
+        .map_err(|_| Error::PayloadTooLarge)
+        .inspect_err(telemetry::debug)?;
+    let fingerprint =
+        Fingerprint::new(parts.method.as_str(), &parts.uri.to_string(), &body);
+    let idempotency = match claim_key(caller, key, fingerprint, &state).await? {
+        Claim::Started(idempotency) => idempotency,
+        Claim::Replayed(stored) => return Ok(replay(stored)),
+    };
+    let response = next.run(Request::from_parts(parts, Body::from(body))).await;
+    if response.status().is_server_error() {
+        idempotency.release().await?;
+        return Ok(response);
+    }
+    let (parts, body) = response.into_parts();
+    let body = to_bytes(body, usize::MAX)
+        .await
+        .context("read response body")?;
+    let stored = StoredResponse {
+        status: parts.status.as_u16(),
+        content_type: parts
+            .headers
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_owned),
+        body: body.to_vec(),
+    };
+    if let Err(e) = idempotency.complete(&stored).await {
+        telemetry::error(&e);
+    }
+    Ok(Response::from_parts(parts, Body::from(body)))
+}
+
+async fn resolve_caller(parts: &mut Parts, state: &AppState) -> Option<Caller> {
+    if parts.headers.contains_key(API_KEY) {
+        DeviceId::from_request_parts(parts, state)
+            .await
+            .map(Caller::Device)
+            .ok()
+    } else {
+        UserId::from_request_parts(parts, state)
+            .await
+            .map(Caller::User)
+            .ok()
+    }
+}
+
+fn replay(stored: StoredResponse) -> Response {
+    let status = StatusCode::from_u16(stored.status)
+        .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+    let mut response = (status, stored.body).into_response();
+    let headers = response.headers_mut();
+    headers.remove(CONTENT_TYPE);
+    if let Some(value) = stored
+        .content_type
+        .and_then(|value| HeaderValue::from_str(&value).ok())
+    {
+        headers.insert(CONTENT_TYPE, value);
+    }
+    headers.insert(IDEMPOTENT_REPLAYED, HeaderValue::from_static("true"));
+    response
+}
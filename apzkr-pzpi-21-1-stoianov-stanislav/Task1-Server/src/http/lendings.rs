@@ -1,36 +1,121 @@
 use axum::{
-    extract::{Path, State},
-    http::StatusCode,
+    extract::{FromRequestParts, Path, Query, State},
+    http::{request::Parts, StatusCode},
+    middleware::from_fn_with_state,
     routing::{get, post},
     Form, Json, Router,
 };
 
 use crate::{
     auth::UserId,
-    lendings::{active_lendings, lend_book, return_book},
+    devices::DeviceId,
+    export::ExportOptions,
+    holds::hold_queue,
+    lendings::{
+        active_lendings, export_lendings, lend_book, my_lendings,
+        renew_lending, return_book, Lender,
+    },
     state::AppState,
+    Error,
 };
 
-pub fn router() -> Router<AppState> {
+use super::{devices::API_KEY, idempotency::idempotency};
+
+pub fn router(state: AppState) -> Router<AppState> {
     Router::new()
         .route(
-            "/new",
-            post(|State(state), Form(lending)| async move {
-                lend_book(lending, state).await.map(|_| StatusCode::CREATED)
+            "/my",
+            get(|user_id: UserId, Query(filter), State(state)| async move {
+                my_lendings(user_id, filter, state).await.map(Json)
             }),
         )
         .route(
-            "/:library_id/pending",
+            "/:id/renew",
+            post(|user_id: UserId, Path(id), State(state)| async move {
+                renew_lending(user_id, id, state).await.map(Json)
+            }),
+        )
+        .route_layer(from_fn_with_state(state, idempotency))
+}
+
+pub fn library_router() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/new",
+            post(
+                |lender: Lender,
+                 Path(id),
+                 State(state),
+                 Form(lending)| async move {
+                    lend_book(lender, id, lending, state)
+                        .await
+                        .map(|_| StatusCode::CREATED)
+                },
+            ),
+        )
+        .route(
+            "/export",
             get(
-                |owner_id: UserId, Path(library_id), State(state)| async move {
-                    active_lendings(owner_id, library_id, state).await.map(Json)
+                |owner_id: UserId,
+                 Path(id),
+                 Query(ExportOptions { format }),
+                 Query(period),
+                 State(state)| async move {
+                    export_lendings(owner_id, id, format, period, state).await
                 },
             ),
         )
         .route(
-            "/return",
-            post(|State(state), Form(return_request)| async move {
-                return_book(return_request, state).await
+            "/pending",
+            get(
+                |owner_id: UserId,
+                 Path(id),
+                 Query(filter),
+                 Query(page),
+                 State(state)| async move {
+                    active_lendings(owner_id, id, filter, page, state)
+                        .await
+                        .map(Json)
+                },
+            ),
+        )
+        .route(
+            "/holds",
+            get(|owner_id: UserId, Path(id), State(state)| async move {
+                hold_queue(owner_id, id, state).await.map(Json)
             }),
         )
+        .route(
+            "/return",
+            post(
+                |lender: Lender,
+                 Path(id),
+                 State(state),
+                 Form(return_request)| async move {
+                    return_book(lender, id, return_request, state)
+                        .await
+                        .map(Json)
+                },
+            ),
+        )
+}
+
+#[axum::async_trait]
+impl FromRequestParts<AppState> for Lender {
+    type Rejection = Error;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &AppState,
+    ) -> Result<Self, Self::Rejection> {
+        if parts.headers.contains_key(API_KEY) {
+            DeviceId::from_request_parts(parts, state)
+                .await
+                .map(Lender::Device)
+        } else {
+            UserId::from_request_parts(parts, state)
+                .await
+                .map(Lender::Owner)
+        }
+    }
 }
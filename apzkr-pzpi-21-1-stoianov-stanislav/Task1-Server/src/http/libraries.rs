@@ -1,6 +1,8 @@
 use axum::{
-    extract::{Path, State},
+    body::Bytes,
+    extract::{Path, Query, State},
     http::StatusCode,
+    middleware::from_fn_with_state,
     routing::{delete, get, post, put},
     Form, Json, Router,
 };
@@ -8,8 +10,13 @@ use axum::{
 use crate::{
     auth::UserId,
     books::{
-        add_book, delete_book, list_library_books, update_book, view_book,
+        add_book, delete_book, export_books, import_books, list_library_books,
+        opds_book, opds_books, opds_facets, opds_root, opds_search,
+        update_book, view_book, BookExportOptions, OpdsFacet,
     },
+    copies::{add_copy, delete_copy, list_copies, update_copy},
+    holds::place_hold,
+    ledger::{record_adjustment, record_payment},
     libraries::{
         add_library, delete_library, list_libraries, list_my_libraries,
         update_library, view_library,
@@ -17,12 +24,45 @@ use crate::{
     state::AppState,
 };
 
-pub fn router() -> Router<AppState> {
+use super::{devices, idempotency::idempotency, lendings};
+
+pub fn router(state: AppState) -> Router<AppState> {
     Router::new()
         .nest("/:id/books", books_router())
+        .nest("/:id/devices", devices::router())
+        .nest("/:id/lendings", lendings::library_router())
+        .nest("/:id/opds", opds_router())
         .route(
             "/",
-            get(|State(state)| async move { list_libraries(state).await.map(Json) }),
+            get(|Query(page), State(state)| async move {
+                list_libraries(page, state).await.map(Json)
+            }),
+        )
+        .route(
+            "/:id/payments",
+            post(
+                |owner_id: UserId,
+                 Path(library_id),
+                 State(state),
+                 Form(payment)| async move {
+                    record_payment(owner_id, library_id, payment, state)
+                        .await
+                        .map(|_| StatusCode::CREATED)
+                },
+            ),
+        )
+        .route(
+            "/:id/adjustments",
+            post(
+                |owner_id: UserId,
+                 Path(library_id),
+                 State(state),
+                 Form(adjustment)| async move {
+                    record_adjustment(owner_id, library_id, adjustment, state)
+                        .await
+                        .map(|_| StatusCode::CREATED)
+                },
+            ),
         )
         .route(
             "/my",
@@ -58,10 +98,12 @@ pub fn router() -> Router<AppState> {
                     .map(|_| StatusCode::OK)
             }),
         )
+        .route_layer(from_fn_with_state(state, idempotency))
 }
 
 fn books_router() -> Router<AppState> {
     Router::new()
+        .nest("/:id/copies", copies_router())
         .route(
             "/",
             post(
@@ -77,9 +119,51 @@ fn books_router() -> Router<AppState> {
         )
         .route(
             "/",
-            get(|Path(library_id), State(state)| async move {
-                list_library_books(library_id, state).await.map(Json)
-            }),
+            get(
+                |Path(library_id),
+                 Query(filter),
+                 Query(page),
+                 State(state)| async move {
+                    list_library_books(library_id, filter, page, state)
+                        .await
+                        .map(Json)
+                },
+            ),
+        )
+        .route(
+            "/export",
+            get(
+                |owner_id: UserId,
+                 Path(library_id),
+                 Query(BookExportOptions { format }),
+                 State(state)| async move {
+                    export_books(owner_id, library_id, format, state).await
+                },
+            ),
+        )
+        .route(
+            "/import",
+            post(
+                |owner_id: UserId,
+                 Path(library_id),
+                 Query(options),
+                 State(state),
+                 data: Bytes| async move {
+                    let data = data.to_vec();
+                    import_books(owner_id, library_id, options, data, state)
+                        .await
+                        .map(|report| {
+                            let status = if !report.errors.is_empty() {
+                                StatusCode::UNPROCESSABLE_ENTITY
+                            } else if report.dry_run {
+                                StatusCode::OK
+                            } else {
+                                StatusCode::CREATED
+                            };
+                            (status, Json(report))
+                        })
+                },
+            ),
         )
         .route(
             "/:id",
@@ -109,4 +193,109 @@ fn books_router() -> Router<AppState> {
                 },
             ),
         )
+        .route(
+            "/:id/holds",
+            post(
+                |user_id: UserId,
+                 Path((library_id, book_id)),
+                 State(state)| async move {
+                    place_hold(user_id, library_id, book_id, state)
+                        .await
+                        .map(|_| StatusCode::CREATED)
+                },
+            ),
+        )
+}
+
+fn opds_router() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/",
+            get(|Path(library_id), State(state)| async move {
+                opds_root(library_id, state).await
+            }),
+        )
+        .route(
+            "/genres",
+            get(|Path(library_id), State(state)| async move {
+                opds_facets(library_id, OpdsFacet::Genre, state).await
+            }),
+        )
+        .route(
+            "/authors",
+            get(|Path(library_id), State(state)| async move {
+                opds_facets(library_id, OpdsFacet::Author, state).await
+            }),
+        )
+        .route(
+            "/books",
+            get(
+                |Path(library_id),
+                 Query(filter),
+                 Query(page),
+                 State(state)| async move {
+                    opds_books(library_id, filter, page, state).await
+                },
+            ),
+        )
+        .route(
+            "/books/:book_id",
+            get(|Path((library_id, book_id)), State(state)| async move {
+                opds_book(library_id, book_id, state).await
+            }),
+        )
+        .route(
+            "/search.xml",
+            get(|Path(library_id), State(state)| async move {
+                opds_search(library_id, state).await
+            }),
+        )
+}
+
+fn copies_router() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/",
+            post(
+                |owner_id: UserId,
+                 Path((library_id, book_id)),
+                 State(state),
+                 Form(copy)| async move {
+                    add_copy(owner_id, library_id, book_id, copy, state)
+                        .await
+                        .map(|_| StatusCode::CREATED)
+                },
+            ),
+        )
+        .route(
+            "/",
+            get(|Path((library_id, book_id)), State(state)| async move {
+                list_copies(library_id, book_id, state).await.map(Json)
+            }),
+        )
+        .route(
+            "/:id",
+            put(
+                |owner_id: UserId,
+                 Path((library_id, book_id, copy_id)),
+                 State(state),
+                 Form(copy)| async move {
+                    update_copy(
+                        owner_id, library_id, book_id, copy_id, copy, state,
+                    )
+                    .await
+                },
+            ),
+        )
+        .route(
+            "/:id",
+            delete(
+                |owner_id: UserId,
+                 Path((library_id, book_id, copy_id)),
+                 State(state)| async move {
+                    delete_copy(owner_id, library_id, book_id, copy_id, state)
+                        .await
+                },
+            ),
+        )
 }
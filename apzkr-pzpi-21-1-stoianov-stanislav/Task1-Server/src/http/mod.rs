@@ -1,8 +1,14 @@
 mod auth;
 mod backup;
+mod books;
+mod devices;
 mod error;
+mod export;
+mod holds;
+mod idempotency;
 mod lendings;
 mod libraries;
+mod opds;
 
 use std::net::SocketAddr;
 
@@ -14,7 +20,7 @@ use tower_http::cors::CorsLayer;
 use crate::{config::HttpConfig, state::AppState};
 
 pub async fn serve(config: HttpConfig, state: AppState) -> anyhow::Result<()> {
-    let router = router().with_state(state);
+    let router = router(state.clone()).with_state(state);
     let addr = SocketAddr::from((config.host, config.port));
     let listener = TcpListener::bind(addr).await?;
     axum::serve(listener, router.into_make_service())
@@ -22,11 +28,13 @@ pub async fn serve(config: HttpConfig, state: AppState) -> anyhow::Result<()> {
         .context("start http server")
 }
 
-fn router() -> Router<AppState> {
+fn router(state: AppState) -> Router<AppState> {
     Router::new()
         .nest("/auth", auth::router())
-        .nest("/libraries", libraries::router())
-        .nest("/lendings", lendings::router())
+        .nest("/libraries", libraries::router(state.clone()))
+        .nest("/books", books::router())
+        .nest("/lendings", lendings::router(state))
+        .nest("/holds", holds::router())
         .nest("/backup", backup::router())
         .layer(CorsLayer::very_permissive())
 }
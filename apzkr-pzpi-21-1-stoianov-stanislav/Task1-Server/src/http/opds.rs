@@ -0,0 +1,12 @@
+use axum::{
+    http::header::CONTENT_TYPE,
+    response::{IntoResponse, Response},
+};
+
+use crate::books::OpdsDocument;
+
+impl IntoResponse for OpdsDocument {
+    fn into_response(self) -> Response {
+        ([(CONTENT_TYPE, self.content_type)], self.body).into_response()
+    }
+}
@@ -0,0 +1,310 @@
+use chrono::{DateTime, Utc};
+use sha2::{Digest, Sha256};
+
+use crate::{
+    auth::UserId, database::Database, devices::DeviceId, state::AppState,
+    telemetry, Error,
+};
+
+pub type UnvalidatedIdempotencyKey = String;
+
+#[derive(Clone, Debug, sqlx::Type)]
+#[sqlx(transparent)]
+pub struct IdempotencyKey(UnvalidatedIdempotencyKey);
+
+impl IdempotencyKey {
+    pub fn new(key: UnvalidatedIdempotencyKey) -> crate::Result<Self> {
+        if key.is_empty() || key.len() > 255 {
+            Err(Error::Validation(
+                "idempotency key must be between 1 and 255 characters long",
+            ))
+        } else {
+            Ok(Self(key))
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, sqlx::Type)]
+#[sqlx(transparent)]
+pub struct Fingerprint(Vec<u8>);
+
+impl Fingerprint {
+    pub fn new(method: &str, uri: &str, body: &[u8]) -> Self {
+        let mut hasher = Sha256::new();
+        for part in [method.as_bytes(), uri.as_bytes(), body] {
+            hasher.update((part.len() as u64).to_be_bytes());
+            hasher.update(part);
+        }
+        Self(hasher.finalize().to_vec())
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct StoredResponse {
+    pub status: u16,
+    pub content_type: Option<String>,
+    pub body: Vec<u8>,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum Caller {
+    User(UserId),
+    Device(DeviceId),
+}
+
+#[derive(Debug)]
+pub enum Claim {
+    Started(Idempotency),
+    Replayed(StoredResponse),
+}
+
+#[derive(Debug)]
+pub struct Idempotency {
+    caller: DbCaller,
+    key: IdempotencyKey,
+    claimed_at: DateTime<Utc>,
+    database: Database,
+    finished: bool,
+}
+
+#[tracing::instrument(skip(state))]
+pub async fn claim_key(
+    caller: Caller,
+    key: IdempotencyKey,
+    fingerprint: Fingerprint,
+    state: &AppState,
+) -> crate::Result<Claim> {
+    let caller = match caller {
+        Caller::User(user_id) => user_id
+            .sql_id(&state.id_cipher)
+            .map(|id| DbCaller { kind: "user", id })
+            .map_err(|_| Error::LoggedOff),
+        Caller::Device(device_id) => device_id
+            .sql_id(&state.id_cipher)
+            .map(|id| DbCaller { kind: "device", id })
+            .map_err(|_| Error::InvalidApiKey),
+    }
+    .inspect_err(telemetry::debug)?;
+    let config = &state.idempotency_config;
+    delete_expired_keys(config.ttl.as_secs_f64(), &state.database).await?;
+    let lock_timeout = config.lock_timeout.as_secs_f64();
+    if let Some((claimed_at,)) =
+        insert_key(caller, &key, &fingerprint, lock_timeout, &state.database)
+            .await?
+    {
+        return Ok(Claim::Started(Idempotency {
+            caller,
+            key,
+            claimed_at,
+            database: state.database.clone(),
+            finished: false,
+        }));
+    }
+    let stored = get_key(caller, &key, &state.database)
+        .await?
+        .ok_or(Error::RequestInProgress)
+        .inspect_err(telemetry::debug)?;
+    if stored.fingerprint != fingerprint {
+        return Err(Error::Validation(
+            "idempotency key is already used for a different request",
+        ))
+        .inspect_err(telemetry::debug);
+    }
+    match (stored.status, stored.body) {
+        (Some(status), Some(body)) => Ok(Claim::Replayed(StoredResponse {
+            status: status as u16,
+            content_type: stored.content_type,
+            body,
+        })),
+        _ => Err(Error::RequestInProgress).inspect_err(telemetry::debug),
+    }
+}
+
+impl Idempotency {
+    #[tracing::instrument(skip(self, response))]
+    pub async fn complete(
+        mut self,
+        response: &StoredResponse,
+    ) -> crate::Result<()> {
+        self.finished = true;
+        let saved = save_response(
+            self.caller,
+            &self.key,
+            self.claimed_at,
+            response,
+            &self.database,
+        )
+        .await;
+        if saved.is_err() {
+            self.finished = false;
+            self.release().await?;
+        }
+        saved
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn release(mut self) -> crate::Result<()> {
+        self.finished = true;
+        delete_claim(self.caller, &self.key, self.claimed_at, &self.database)
+            .await
+    }
+}
+
+impl Drop for Idempotency {
+    fn drop(&mut self) {
+        if self.finished {
+            return;
+        }
+        let (caller, key, claimed_at, database) = (
+            self.caller,
+            self.key.clone(),
+            self.claimed_at,
+            self.database.clone(),
+        );
+        tokio::spawn(async move {
+            delete_claim(caller, &key, claimed_at, &database).await.ok();
+        });
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct DbCaller {
+    kind: &'static str,
+    id: i64,
+}
+
+#[derive(Clone, Debug, sqlx::FromRow)]
+struct DbKey {
+    fingerprint: Fingerprint,
+    status: Option<i16>,
+    content_type: Option<String>,
+    body: Option<Vec<u8>>,
+}
+
+#[tracing::instrument(skip(db), err(Debug))]
+async fn delete_expired_keys(ttl: f64, db: &Database) -> crate::Result<()> {
+    sqlx::query(
+        "
+        delete from idempotency_keys
+        where created_at < now() - make_interval(secs => $1);
+        ",
+    )
+    .bind(ttl)
+    .execute(db)
+    .await
+    .map(|_| ())
+    .map_err(Error::from)
+}
+
+#[tracing::instrument(skip(db), err(Debug))]
+async fn insert_key(
+    caller: DbCaller,
+    key: &IdempotencyKey,
+    fingerprint: &Fingerprint,
+    lock_timeout: f64,
+    db: &Database,
+) -> crate::Result<Option<(DateTime<Utc>,)>> {
+    sqlx::query_as(
+        "
+        insert into idempotency_keys as k
+          (caller_kind, caller_id, key, fingerprint, created_at)
+        values
+          ($1, $2, $3, $4, now())
+        on conflict (caller_kind, caller_id, key) do update
+        set created_at = excluded.created_at
+        where k.status is null
+          and k.fingerprint = excluded.fingerprint
+          and k.created_at < now() - make_interval(secs => $5)
+        returning created_at;
+        ",
+    )
+    .bind(caller.kind)
+    .bind(caller.id)
+    .bind(key)
+    .bind(fingerprint)
+    .bind(lock_timeout)
+    .fetch_optional(db)
+    .await
+    .map_err(Error::from)
+}
+
+#[tracing::instrument(skip(db), err(Debug))]
+async fn get_key(
+    caller: DbCaller,
+    key: &IdempotencyKey,
+    db: &Database,
+) -> crate::Result<Option<DbKey>> {
+    sqlx::query_as(
+        "
+        select fingerprint, status, content_type, body
+        from idempotency_keys
+        where caller_kind = $1
+          and caller_id = $2
+          and key = $3;
+        ",
+    )
+    .bind(caller.kind)
+    .bind(caller.id)
+    .bind(key)
+    .fetch_optional(db)
+    .await
+    .map_err(Error::from)
+}
+
+#[tracing::instrument(skip(response, db), err(Debug))]
+async fn save_response(
+    caller: DbCaller,
+    key: &IdempotencyKey,
+    claimed_at: DateTime<Utc>,
+    response: &StoredResponse,
+    db: &Database,
+) -> crate::Result<()> {
+    sqlx::query(
+        "
+        update idempotency_keys
+        set (status, content_type, body) = ($1, $2, $3)
+        where caller_kind = $4
+          and caller_id = $5
+          and key = $6
+          and created_at = $7;
+        ",
+    )
+    .bind(response.status as i16)
+    .bind(&response.content_type)
+    .bind(&response.body)
+    .bind(caller.kind)
+    .bind(caller.id)
+    .bind(key)
+    .bind(claimed_at)
+    .execute(db)
+    .await
+    .map(|_| ())
+    .map_err(Error::from)
+}
+
+#[tracing::instrument(skip(db), err(Debug))]
+async fn delete_claim(
+    caller: DbCaller,
+    key: &IdempotencyKey,
+    claimed_at: DateTime<Utc>,
+    db: &Database,
+) -> crate::Result<()> {
+    sqlx::query(
+        "
+        delete from idempotency_keys
+        where caller_kind = $1
+          and caller_id = $2
+          and key = $3
+          and created_at = $4
+          and status is null;
+        ",
+    )
+    .bind(caller.kind)
+    .bind(caller.id)
+    .bind(key)
+    .bind(claimed_at)
+    .execute(db)
+    .await
+    .map(|_| ())
+    .map_err(Error::from)
+}
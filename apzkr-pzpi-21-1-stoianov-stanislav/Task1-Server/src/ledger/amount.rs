@@ -0,0 +1,48 @@
+use serde::Serialize;
+use sqlx::types::Decimal;
+
+use crate::Error;
+
+pub type UnvalidatedAmount = Decimal;
+
+#[derive(Clone, Copy, Debug, Default, sqlx::Type, Serialize)]
+#[sqlx(transparent)]
+#[serde(transparent)]
+pub struct Amount(UnvalidatedAmount);
+
+impl Amount {
+    pub fn new(amount: UnvalidatedAmount) -> crate::Result<Self> {
+        match amount.normalize() {
+            a if a.is_zero() => Err(Error::Validation("amount cannot be zero")),
+            a if a.scale() > 2 => {
+                Err(Error::Validation("amount cannot have fractions of cents"))
+            }
+            a if a.abs() >= Decimal::new(100_000_000, 0) => {
+                Err(Error::Validation("amount is too large"))
+            }
+            a => Ok(Self(a)),
+        }
+    }
+
+    pub fn payment(amount: UnvalidatedAmount) -> crate::Result<Self> {
+        if amount.is_sign_negative() {
+            Err(Error::Validation("payment must be positive"))
+        } else {
+            Self::new(-amount)
+        }
+    }
+}
+
+impl TryFrom<UnvalidatedAmount> for Amount {
+    type Error = Error;
+
+    fn try_from(value: UnvalidatedAmount) -> Result<Self, Self::Error> {
+        Self::new(value)
+    }
+}
+
+impl From<Amount> for UnvalidatedAmount {
+    fn from(value: Amount) -> Self {
+        value.0
+    }
+}
@@ -0,0 +1,100 @@
+use sqlx::types::Decimal;
+
+use crate::{
+    auth::UserId,
+    database::Database,
+    libraries::{BalanceLimit, Currency, LibraryId, Name},
+    state::AppState,
+    telemetry, Error,
+};
+
+use super::Balance;
+
+#[tracing::instrument(skip(state))]
+pub async fn user_balance(
+    user_id: UserId,
+    state: AppState,
+) -> crate::Result<Vec<Balance>> {
+    let user_id = user_id
+        .sql_id(&state.id_cipher)
+        .map_err(|_| Error::LoggedOff)
+        .inspect_err(telemetry::debug)?;
+    get_balances(user_id, &state.database)
+        .await
+        .map(|balances| {
+            balances
+                .into_iter()
+                .map(|balance| Balance {
+                    library_id: LibraryId::new(
+                        balance.library_id,
+                        &state.id_cipher,
+                    ),
+                    library_name: balance.library_name,
+                    currency: balance.currency,
+                    balance: balance.balance,
+                    balance_limit: balance.balance_limit,
+                })
+                .collect()
+        })
+}
+
+#[tracing::instrument(skip(db))]
+pub async fn check_balance_limit(
+    user_id: i64,
+    library_id: i64,
+    db: &Database,
+) -> crate::Result<()> {
+    sqlx::query_as::<_, (Option<bool>,)>(
+        "
+        select coalesce(sum(e.amount), 0) > lib.balance_limit
+        from libraries lib
+        left join ledger_entries e
+          on e.library_id = lib.id
+          and e.user_id = $1
+        where lib.id = $2
+        group by lib.balance_limit;
+        ",
+    )
+    .bind(user_id)
+    .bind(library_id)
+    .fetch_optional(db)
+    .await
+    .map_err(Error::from)
+    .and_then(|exceeded| match exceeded {
+        Some((Some(true),)) => {
+            Err(Error::BalanceLimitExceeded).inspect_err(telemetry::debug)
+        }
+        _ => Ok(()),
+    })
+}
+
+#[derive(Clone, Debug, sqlx::FromRow)]
+struct DbBalance {
+    library_id: i64,
+    library_name: Name,
+    currency: Currency,
+    balance: Decimal,
+    balance_limit: Option<BalanceLimit>,
+}
+
+#[tracing::instrument(skip(db), err(Debug))]
+async fn get_balances(
+    user_id: i64,
+    db: &Database,
+) -> crate::Result<Vec<DbBalance>> {
+    sqlx::query_as(
+        "
+        select lib.id as library_id, lib.name as library_name, e.currency,
+          sum(e.amount) as balance, lib.balance_limit
+        from ledger_entries e
+        join libraries lib on lib.id = e.library_id
+        where e.user_id = $1
+        group by lib.id, e.currency
+        order by lib.name;
+        ",
+    )
+    .bind(user_id)
+    .fetch_all(db)
+    .await
+    .map_err(Error::from)
+}
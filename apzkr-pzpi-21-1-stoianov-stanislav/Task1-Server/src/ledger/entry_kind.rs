@@ -0,0 +1,8 @@
+#[derive(Clone, Copy, Debug, sqlx::Type)]
+#[sqlx(type_name = "varchar", rename_all = "snake_case")]
+pub enum EntryKind {
+    LendingCharge,
+    OverduePenalty,
+    Adjustment,
+    Payment,
+}
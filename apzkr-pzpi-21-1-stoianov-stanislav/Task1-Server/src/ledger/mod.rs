@@ -0,0 +1,43 @@
+mod amount;
+mod entry_kind;
+
+mod balance;
+mod record;
+
+pub use balance::{check_balance_limit, user_balance};
+pub use record::{record_adjustment, record_lending_charge, record_payment};
+
+use serde::{Deserialize, Serialize};
+use sqlx::types::Decimal;
+
+use crate::{
+    auth::UserId,
+    libraries::{BalanceLimit, Currency, LibraryId, Name},
+};
+
+use self::amount::UnvalidatedAmount;
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NewPayment {
+    pub user_id: UserId,
+    pub amount: UnvalidatedAmount,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NewAdjustment {
+    pub user_id: UserId,
+    pub amount: UnvalidatedAmount,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Balance {
+    pub library_id: LibraryId,
+    pub library_name: Name,
+    pub currency: Currency,
+    pub balance: Decimal,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub balance_limit: Option<BalanceLimit>,
+}
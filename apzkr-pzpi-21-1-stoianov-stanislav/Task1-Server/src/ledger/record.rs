@@ -0,0 +1,150 @@
+use sqlx::error::ErrorKind;
+
+use crate::{
+    auth::UserId,
+    books::check_owns,
+    database::{error_kind, Database, Transaction},
+    lendings::Charge,
+    libraries::LibraryId,
+    state::AppState,
+    telemetry, Error,
+};
+
+use super::{amount::Amount, entry_kind::EntryKind, NewAdjustment, NewPayment};
+
+#[tracing::instrument(skip(state))]
+pub async fn record_payment(
+    owner_id: UserId,
+    library_id: LibraryId,
+    payment: NewPayment,
+    state: AppState,
+) -> crate::Result<()> {
+    let amount = Amount::payment(payment.amount)?;
+    record_entry(
+        owner_id,
+        library_id,
+        payment.user_id,
+        EntryKind::Payment,
+        amount,
+        state,
+    )
+    .await
+}
+
+#[tracing::instrument(skip(state))]
+pub async fn record_adjustment(
+    owner_id: UserId,
+    library_id: LibraryId,
+    adjustment: NewAdjustment,
+    state: AppState,
+) -> crate::Result<()> {
+    let amount = Amount::new(adjustment.amount)?;
+    record_entry(
+        owner_id,
+        library_id,
+        adjustment.user_id,
+        EntryKind::Adjustment,
+        amount,
+        state,
+    )
+    .await
+}
+
+#[tracing::instrument(skip(tx), err(Debug))]
+pub async fn record_lending_charge(
+    user_id: i64,
+    library_id: i64,
+    lending_id: i64,
+    charge: &Charge,
+    tx: &mut Transaction<'_>,
+) -> crate::Result<()> {
+    let fees = [
+        (EntryKind::LendingCharge, charge.lending_fee),
+        (EntryKind::OverduePenalty, charge.overdue_fee),
+    ];
+    for (kind, fee) in fees.into_iter().filter(|(_, fee)| !fee.is_zero()) {
+        sqlx::query(
+            "
+            insert into ledger_entries
+              (user_id, library_id, lending_id, kind, amount, currency,
+               recorded_at)
+            values
+              ($1, $2, $3, $4, $5, $6, now());
+            ",
+        )
+        .bind(user_id)
+        .bind(library_id)
+        .bind(lending_id)
+        .bind(kind)
+        .bind(fee)
+        .bind(&charge.currency)
+        .execute(&mut **tx)
+        .await?;
+    }
+    Ok(())
+}
+
+async fn record_entry(
+    owner_id: UserId,
+    library_id: LibraryId,
+    user_id: UserId,
+    kind: EntryKind,
+    amount: Amount,
+    state: AppState,
+) -> crate::Result<()> {
+    let owner_id = owner_id
+        .sql_id(&state.id_cipher)
+        .map_err(|_| Error::LoggedOff)
+        .inspect_err(telemetry::debug)?;
+    let library_id = library_id
+        .sql_id(&state.id_cipher)
+        .map_err(|_| Error::NotFound)
+        .inspect_err(telemetry::debug)?;
+    check_owns(owner_id, library_id, &state.database).await?;
+    let entry = DbEntry {
+        user_id: user_id
+            .sql_id(&state.id_cipher)
+            .map_err(|_| Error::NotFound)
+            .inspect_err(telemetry::debug)?,
+        library_id,
+        kind,
+        amount,
+    };
+    save_entry(&entry, &state.database).await
+}
+
+#[derive(Clone, Debug)]
+struct DbEntry {
+    user_id: i64,
+    library_id: i64,
+    kind: EntryKind,
+    amount: Amount,
+}
+
+#[tracing::instrument(skip(db))]
+async fn save_entry(entry: &DbEntry, db: &Database) -> crate::Result<()> {
+    match sqlx::query(
+        "
+        insert into ledger_entries
+          (user_id, library_id, kind, amount, currency, recorded_at)
+        select $1, id, $3, $4, currency, now()
+        from libraries
+        where id = $2;
+        ",
+    )
+    .bind(entry.user_id)
+    .bind(entry.library_id)
+    .bind(entry.kind)
+    .bind(entry.amount)
+    .execute(db)
+    .await
+    {
+        Err(e) if error_kind(&e) == Some(ErrorKind::ForeignKeyViolation) => {
+            Err(Error::NotFound).inspect_err(telemetry::debug)
+        }
+        other => other
+            .map(|_| ())
+            .map_err(Error::from)
+            .inspect_err(telemetry::error),
+    }
+}
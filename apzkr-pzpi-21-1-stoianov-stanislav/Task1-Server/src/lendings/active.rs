@@ -1,20 +1,30 @@
 use crate::{
-    auth::{get_user, UserId},
-    books::{check_owns, view_book, BookId},
+    auth::{self, Email, User, UserId},
+    books::{self, check_owns, Author, Book, BookId, Genre, Isbn, Year},
+    copies::{Barcode, CopyId},
     database::Database,
-    libraries::LibraryId,
+    libraries::{self, LibraryId},
+    page::{check_cursor, Page, PageRequest},
     state::AppState,
     telemetry, Error,
 };
 
-use super::{due_date::DueDate, lending_date::LendingDate, Lending, LendingId};
+use super::{
+    charge::{Charge, Rates},
+    due_date::DueDate,
+    lending_date::LendingDate,
+    return_date::ReturnDate,
+    ActiveLendingFilter, Lending, LendingId, LendingSort,
+};
 
 #[tracing::instrument(skip(state))]
 pub async fn active_lendings(
     owner_id: UserId,
     library_id: LibraryId,
+    filter: ActiveLendingFilter,
+    page: PageRequest<LendingId, LendingSort>,
     state: AppState,
-) -> crate::Result<Vec<Lending>> {
+) -> crate::Result<Page<Lending, LendingId>> {
     let db_owner_id = owner_id
         .sql_id(&state.id_cipher)
         .map_err(|_| Error::LoggedOff)
@@ -24,52 +34,115 @@ pub async fn active_lendings(
         .map_err(|_| Error::NotFound)
         .inspect_err(telemetry::debug)?;
     check_owns(db_owner_id, db_library_id, &state.database).await?;
-    let db_lendings =
-        get_active_lendings(db_library_id, &state.database).await?;
-    let mut lendings = Vec::with_capacity(db_lendings.len());
-    for lending in db_lendings {
-        let id = LendingId::new(lending.id, &state.id_cipher);
-        let book_id = BookId::new(lending.book_id, &state.id_cipher);
-        let lendee_id = UserId::new(lending.lendee_id, &state.id_cipher);
-        let book = view_book(library_id, book_id, state.clone()).await?;
-        let lendee = get_user(lendee_id, state.clone()).await?;
-        let lending = Lending {
-            id,
-            book,
-            lendee,
+    let cursor = page.sql_cursor(&state.id_cipher)?;
+    check_cursor("lendings", cursor, &state.database).await?;
+    let limit = page.limit();
+    let lendings = get_active_lendings(
+        db_library_id,
+        filter.overdue,
+        cursor,
+        limit,
+        page.sort,
+        &state.database,
+    )
+    .await?
+    .into_iter()
+    .map(|lending| {
+        let charge = Charge::new(
+            lending.lent_on.clone(),
+            lending.due.clone(),
+            ReturnDate::today(),
+            lending.rates,
+        );
+        Lending {
+            id: LendingId::new(lending.id, &state.id_cipher),
+            book: Book {
+                id: BookId::new(lending.book_id, &state.id_cipher),
+                year: lending.year,
+                name: lending.book_name,
+                genre: lending.genre,
+                author: lending.author,
+                isbn: lending.isbn,
+            },
+            copy_id: CopyId::new(lending.copy_id, &state.id_cipher),
+            barcode: lending.barcode,
+            library_id,
+            library_name: lending.library_name,
+            lendee: User {
+                id: UserId::new(lending.lendee_id, &state.id_cipher),
+                name: lending.lendee_name,
+                email: lending.lendee_email,
+            },
             lent_on: lending.lent_on,
             due: lending.due,
-        };
-        lendings.push(lending);
-    }
-    Ok(lendings)
+            returned_on: None,
+            renewals: lending.renewals,
+            charge,
+        }
+    })
+    .collect();
+    Ok(Page::new(lendings, limit, |lending| lending.id))
 }
 
 #[derive(Clone, Debug, sqlx::FromRow)]
 struct DbLending {
     id: i64,
-    book_id: i64,
-    lendee_id: i64,
     lent_on: LendingDate,
     due: DueDate,
+    renewals: i16,
+    copy_id: i64,
+    barcode: Barcode,
+    book_id: i64,
+    year: Year,
+    book_name: books::Name,
+    genre: Genre,
+    author: Author,
+    isbn: Option<Isbn>,
+    lendee_id: i64,
+    lendee_name: auth::Name,
+    lendee_email: Email,
+    library_name: libraries::Name,
+    #[sqlx(flatten)]
+    rates: Rates,
 }
 
 #[tracing::instrument(skip(db), err(Debug))]
 async fn get_active_lendings(
     library_id: i64,
+    overdue: bool,
+    cursor: Option<i64>,
+    limit: i64,
+    sort: LendingSort,
     db: &Database,
 ) -> crate::Result<Vec<DbLending>> {
-    sqlx::query_as(
+    sqlx::query_as(&format!(
         "
-        select id, book_id, lendee_id, lent_on, due
-        from lendings
-        where book_id in (
-          select id from books where library_id = $1
-        )
-          and returned_on is null;
+        select l.id, l.lent_on, l.due, l.renewals,
+          l.copy_id, c.barcode,
+          b.id as book_id, b.year, b.name as book_name, b.genre, b.author,
+          b.isbn,
+          u.id as lendee_id, u.name as lendee_name, u.email as lendee_email,
+          lib.name as library_name,
+          lib.daily_rate, lib.overdue_rate, lib.currency
+        from lendings l
+        join copies c on c.id = l.copy_id
+        join books b on b.id = c.book_id
+        join libraries lib on lib.id = b.library_id
+        join users u on u.id = l.lendee_id
+        where b.library_id = $1
+          and l.returned_on is null
+          and (not $2 or l.due < current_date)
+          and ($3::bigint is null
+            or (l.{key}, l.id) > (select {key}, id from lendings where id = $3))
+        order by l.{key}, l.id
+        limit $4 + 1;
         ",
-    )
+        key = sort.column(),
+    ))
     .bind(library_id)
+    .bind(overdue)
+    .bind(cursor)
+    .bind(limit)
     .fetch_all(db)
     .await
     .map_err(Error::from)
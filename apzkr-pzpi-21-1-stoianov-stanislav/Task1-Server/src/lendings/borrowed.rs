@@ -0,0 +1,137 @@
+use crate::{
+    auth::{get_user, UserId},
+    books::{self, Author, Book, BookId, Genre, Isbn, Year},
+    copies::{Barcode, CopyId},
+    database::Database,
+    libraries::{self, LibraryId},
+    state::AppState,
+    telemetry, Error,
+};
+
+use super::{
+    charge::{Charge, Fee, Rates},
+    due_date::DueDate,
+    lending_date::LendingDate,
+    return_date::ReturnDate,
+    Lending, LendingFilter, LendingId, LendingStatus,
+};
+
+#[tracing::instrument(skip(state))]
+pub async fn my_lendings(
+    user_id: UserId,
+    filter: LendingFilter,
+    state: AppState,
+) -> crate::Result<Vec<Lending>> {
+    let db_user_id = user_id
+        .sql_id(&state.id_cipher)
+        .map_err(|_| Error::LoggedOff)
+        .inspect_err(telemetry::debug)?;
+    let lendee = get_user(user_id, state.clone()).await?;
+    get_user_lendings(db_user_id, filter.status, &state.database)
+        .await
+        .map(|lendings| {
+            lendings
+                .into_iter()
+                .map(|lending| {
+                    let charge = match (
+                        lending.returned_on.clone(),
+                        lending.lending_fee,
+                        lending.overdue_fee,
+                    ) {
+                        (Some(_), Some(lending_fee), Some(overdue_fee)) => {
+                            Charge::from_fees(
+                                lending_fee,
+                                overdue_fee,
+                                lending.rates.currency,
+                            )
+                        }
+                        (returned_on, _, _) => Charge::new(
+                            lending.lent_on.clone(),
+                            lending.due.clone(),
+                            returned_on.unwrap_or_else(ReturnDate::today),
+                            lending.rates,
+                        ),
+                    };
+                    Lending {
+                        id: LendingId::new(lending.id, &state.id_cipher),
+                        book: Book {
+                            id: BookId::new(lending.book_id, &state.id_cipher),
+                            year: lending.year,
+                            name: lending.book_name,
+                            genre: lending.genre,
+                            author: lending.author,
+                            isbn: lending.isbn,
+                        },
+                        copy_id: CopyId::new(lending.copy_id, &state.id_cipher),
+                        barcode: lending.barcode,
+                        library_id: LibraryId::new(
+                            lending.library_id,
+                            &state.id_cipher,
+                        ),
+                        library_name: lending.library_name,
+                        lendee: lendee.clone(),
+                        lent_on: lending.lent_on,
+                        due: lending.due,
+                        returned_on: lending.returned_on,
+                        renewals: lending.renewals,
+                        charge,
+                    }
+                })
+                .collect()
+        })
+}
+
+#[derive(Clone, Debug, sqlx::FromRow)]
+struct DbLending {
+    id: i64,
+    lent_on: LendingDate,
+    due: DueDate,
+    returned_on: Option<ReturnDate>,
+    renewals: i16,
+    lending_fee: Option<Fee>,
+    overdue_fee: Option<Fee>,
+    copy_id: i64,
+    barcode: Barcode,
+    book_id: i64,
+    year: Year,
+    book_name: books::Name,
+    genre: Genre,
+    author: Author,
+    isbn: Option<Isbn>,
+    library_id: i64,
+    library_name: libraries::Name,
+    #[sqlx(flatten)]
+    rates: Rates,
+}
+
+#[tracing::instrument(skip(db), err(Debug))]
+async fn get_user_lendings(
+    user_id: i64,
+    status: Option<LendingStatus>,
+    db: &Database,
+) -> crate::Result<Vec<DbLending>> {
+    let active = status.map(|status| matches!(status, LendingStatus::Active));
+    sqlx::query_as(
+        "
+        select l.id, l.lent_on, l.due, l.returned_on, l.renewals,
+          l.lending_fee, l.overdue_fee,
+          l.copy_id, c.barcode,
+          b.id as book_id, b.year, b.name as book_name, b.genre, b.author,
+          b.isbn,
+          lib.id as library_id, lib.name as library_name,
+          lib.daily_rate, lib.overdue_rate, lib.currency
+        from lendings l
+        join copies c on c.id = l.copy_id
+        join books b on b.id = c.book_id
+        join libraries lib on lib.id = b.library_id
+        where l.lendee_id = $1
+          and ($2::boolean is null or (l.returned_on is null) = $2)
+        order by l.lent_on desc, l.id desc;
+        ",
+    )
+    .bind(user_id)
+    .bind(active)
+    .fetch_all(db)
+    .await
+    .map_err(Error::from)
+}
@@ -0,0 +1,65 @@
+use chrono::NaiveDate;
+use serde::Serialize;
+use sqlx::types::Decimal;
+
+use crate::libraries::{Currency, DailyRate, OverdueRate};
+
+use super::{
+    due_date::DueDate, lending_date::LendingDate, return_date::ReturnDate,
+};
+
+pub type Fee = Decimal;
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Charge {
+    pub lending_fee: Fee,
+    pub overdue_fee: Fee,
+    pub total: Fee,
+    pub currency: Currency,
+}
+
+#[derive(Clone, Debug, sqlx::FromRow)]
+pub struct Rates {
+    pub daily_rate: DailyRate,
+    pub overdue_rate: OverdueRate,
+    pub currency: Currency,
+}
+
+impl Charge {
+    pub fn new(
+        lent_on: LendingDate,
+        due: DueDate,
+        returned_on: ReturnDate,
+        rates: Rates,
+    ) -> Self {
+        let lent_on = NaiveDate::from(lent_on);
+        let due = NaiveDate::from(due);
+        let returned_on = NaiveDate::from(returned_on);
+        let daily_rate = Decimal::from(rates.daily_rate);
+        let overdue_rate = Decimal::from(rates.overdue_rate);
+        let days_lent = (returned_on - lent_on).num_days().max(1);
+        let days_overdue = (returned_on - due).num_days().max(0);
+        let lending_fee = Decimal::from(days_lent) * daily_rate;
+        let overdue_fee =
+            Decimal::from(days_overdue) * daily_rate * overdue_rate;
+        Self::from_fees(
+            lending_fee.round_dp(2),
+            overdue_fee.round_dp(2),
+            rates.currency,
+        )
+    }
+
+    pub fn from_fees(
+        lending_fee: Fee,
+        overdue_fee: Fee,
+        currency: Currency,
+    ) -> Self {
+        Self {
+            lending_fee,
+            overdue_fee,
+            total: lending_fee + overdue_fee,
+            currency,
+        }
+    }
+}
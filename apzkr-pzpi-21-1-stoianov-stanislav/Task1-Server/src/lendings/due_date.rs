@@ -1,7 +1,9 @@
 use chrono::{Days, NaiveDate};
 use serde::Serialize;
 
-use super::lending_date::LendingDate;
+use crate::libraries::RenewalPeriod;
+
+use super::{lending_date::LendingDate, return_date::ReturnDate};
 
 pub type LentFor = u64;
 
@@ -15,7 +17,21 @@ impl DueDate {
         Self(NaiveDate::from(lending_date) + Days::new(lent_for))
     }
 
+    pub fn extend(&self, days: RenewalPeriod) -> Self {
+        Self(self.0 + Days::new(i16::from(days) as u64))
+    }
+
+    pub fn is_overdue(&self, today: &ReturnDate) -> bool {
+        NaiveDate::from(today.clone()) > self.0
+    }
+
     pub fn lent_for(&self, lending_date: LendingDate) -> i64 {
         (self.0 - NaiveDate::from(lending_date)).num_days()
     }
 }
+
+impl From<DueDate> for NaiveDate {
+    fn from(value: DueDate) -> Self {
+        value.0
+    }
+}
@@ -0,0 +1,117 @@
+use futures_util::stream::BoxStream;
+
+use crate::{
+    auth::{Email, UserId},
+    books::{self, check_owns, BookId},
+    copies::{Barcode, CopyId},
+    database::Database,
+    devices::DeviceId,
+    export::{export, Export, ExportFormat, Serialized},
+    libraries::LibraryId,
+    state::AppState,
+    telemetry, Error,
+};
+
+use super::{
+    charge::Fee, due_date::DueDate, lending_date::LendingDate,
+    return_date::ReturnDate, ExportedLending, LendingId, LendingPeriod,
+};
+
+#[tracing::instrument(skip(state))]
+pub async fn export_lendings(
+    owner_id: UserId,
+    library_id: LibraryId,
+    format: ExportFormat,
+    period: LendingPeriod,
+    state: AppState,
+) -> crate::Result<Export> {
+    let owner_id = owner_id
+        .sql_id(&state.id_cipher)
+        .map_err(|_| Error::LoggedOff)
+        .inspect_err(telemetry::debug)?;
+    let library_id = library_id
+        .sql_id(&state.id_cipher)
+        .map_err(|_| Error::NotFound)
+        .inspect_err(telemetry::debug)?;
+    if let (Some(from), Some(to)) = (period.from, period.to) {
+        if from > to {
+            return Err(Error::Validation(
+                "period must not end before it starts",
+            ))
+            .inspect_err(telemetry::debug);
+        }
+    }
+    check_owns(owner_id, library_id, &state.database).await?;
+    let cipher = state.id_cipher.clone();
+    Ok(export(
+        format.content_type(),
+        format!("lendings.{}", format.extension()),
+        state.database.clone(),
+        move |db| get_lendings(library_id, period, db),
+        move |lending: DbLending| ExportedLending {
+            id: LendingId::new(lending.id, &cipher),
+            book_id: BookId::new(lending.book_id, &cipher),
+            book_name: lending.book_name,
+            copy_id: CopyId::new(lending.copy_id, &cipher),
+            barcode: lending.barcode,
+            lendee_id: UserId::new(lending.lendee_id, &cipher),
+            lendee_email: lending.lendee_email,
+            lent_on: lending.lent_on,
+            due: lending.due,
+            returned_on: lending.returned_on,
+            renewals: lending.renewals,
+            lending_fee: lending.lending_fee,
+            overdue_fee: lending.overdue_fee,
+            device_id: lending
+                .device_id
+                .map(|device_id| DeviceId::new(device_id, &cipher)),
+        },
+        Box::new(Serialized::new(format)),
+    ))
+}
+
+#[derive(Clone, Debug, sqlx::FromRow)]
+struct DbLending {
+    id: i64,
+    book_id: i64,
+    book_name: books::Name,
+    copy_id: i64,
+    barcode: Barcode,
+    lendee_id: i64,
+    lendee_email: Email,
+    lent_on: LendingDate,
+    due: DueDate,
+    returned_on: Option<ReturnDate>,
+    renewals: i16,
+    lending_fee: Option<Fee>,
+    overdue_fee: Option<Fee>,
+    device_id: Option<i64>,
+}
+
+fn get_lendings(
+    library_id: i64,
+    period: LendingPeriod,
+    db: &Database,
+) -> BoxStream<'_, sqlx::Result<DbLending>> {
+    sqlx::query_as(
+        "
+        select l.id, b.id as book_id, b.name as book_name,
+          l.copy_id, c.barcode,
+          u.id as lendee_id, u.email as lendee_email,
+          l.lent_on, l.due, l.returned_on, l.renewals,
+          l.lending_fee, l.overdue_fee, l.device_id
+        from lendings l
+        join copies c on c.id = l.copy_id
+        join books b on b.id = c.book_id
+        join users u on u.id = l.lendee_id
+        where b.library_id = $1
+          and ($2::date is null or l.lent_on >= $2)
+          and ($3::date is null or l.lent_on <= $3)
+        order by l.lent_on, l.id;
+        ",
+    )
+    .bind(library_id)
+    .bind(period.from)
+    .bind(period.to)
+    .fetch(db)
+}
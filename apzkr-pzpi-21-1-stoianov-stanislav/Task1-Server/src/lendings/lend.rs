@@ -1,57 +1,224 @@
-use crate::{database::Database, state::AppState, telemetry, Error};
+use sqlx::error::ErrorKind;
 
-use super::{due_date::DueDate, lending_date::LendingDate, NewLending};
+use crate::{
+    database::{error_constraint, error_kind, Database, Transaction},
+    holds::claim_hold,
+    ledger::check_balance_limit,
+    libraries::LibraryId,
+    state::AppState,
+    telemetry, Error,
+};
+
+use super::{
+    due_date::DueDate, lender::authorize_lender, lending_date::LendingDate,
+    Lender, NewLending, OperationId,
+};
 
 #[tracing::instrument(skip(state))]
 pub async fn lend_book(
+    lender: Lender,
+    library_id: LibraryId,
     lending: NewLending,
     state: AppState,
 ) -> crate::Result<()> {
-    let lendee_id = lending
-        .lendee_id
+    let library_id = library_id
         .sql_id(&state.id_cipher)
-        .map_err(|_| Error::LoggedOff)
+        .map_err(|_| Error::NotFound)
         .inspect_err(telemetry::debug)?;
-    let book_id = lending
-        .book_id
+    let device_id = authorize_lender(lender, library_id, &state).await?;
+    let operation_id = lending.operation_id;
+    if let Some(operation_id) = operation_id {
+        if lend_replayed(operation_id, library_id, &state.database).await? {
+            return Ok(());
+        }
+    }
+    let lendee_id = lending
+        .lendee_id
         .sql_id(&state.id_cipher)
         .map_err(|_| Error::NotFound)
         .inspect_err(telemetry::debug)?;
+    let lent = match (lending.book_id, lending.copy_id) {
+        (Some(book_id), None) => {
+            book_id.sql_id(&state.id_cipher).map(Lent::Book)
+        }
+        (None, Some(copy_id)) => {
+            copy_id.sql_id(&state.id_cipher).map(Lent::Copy)
+        }
+        _ => {
+            return Err(Error::Validation(
+                "specify either a book or a copy to lend",
+            ))
+        }
+    }
+    .map_err(|_| Error::NotFound)
+    .inspect_err(telemetry::debug)?;
     let lent_on = LendingDate::new(lending.lent_on)?;
     let due = DueDate::new(lent_on.clone(), lending.lent_for);
+    check_balance_limit(lendee_id, library_id, &state.database).await?;
+    let mut tx = state.database.begin().await?;
+    let copy = find_copy(library_id, lent, &mut tx)
+        .await?
+        .ok_or(Error::NotFound)
+        .inspect_err(telemetry::debug)?;
+    if copy.lent {
+        return replayed_or(
+            Error::AlreadyLent,
+            operation_id,
+            library_id,
+            &state,
+        )
+        .await;
+    }
+    if let Err(e) = claim_hold(copy.book_id, lendee_id, &mut tx).await {
+        return replayed_or(e, operation_id, library_id, &state).await;
+    }
     let lending = DbLending {
-        book_id,
+        copy_id: copy.id,
         lendee_id,
         lent_on,
         due,
+        operation_id,
+        device_id,
     };
-    save_lending(&lending, &state.database).await
+    match save_lending(&lending, &mut tx).await {
+        Ok(true) => tx.commit().await.map_err(Error::from),
+        Ok(false) => Ok(()),
+        Err(e) => replayed_or(e, operation_id, library_id, &state).await,
+    }
+}
+
+/// A concurrent replay of the same operation may have taken the copy first,
+/// in which case the conflict it caused is not an error.
+async fn replayed_or(
+    error: Error,
+    operation_id: Option<OperationId>,
+    library_id: i64,
+    state: &AppState,
+) -> crate::Result<()> {
+    match operation_id {
+        Some(operation_id)
+            if matches!(error, Error::AlreadyLent | Error::Reserved)
+                && lend_replayed(operation_id, library_id, &state.database)
+                    .await? =>
+        {
+            Ok(())
+        }
+        _ => Err(error).inspect_err(telemetry::debug),
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Lent {
+    Book(i64),
+    Copy(i64),
+}
+
+#[derive(Clone, Debug, sqlx::FromRow)]
+struct DbCopy {
+    id: i64,
+    book_id: i64,
+    lent: bool,
 }
 
 #[derive(Clone, Debug)]
 struct DbLending {
-    book_id: i64,
+    copy_id: i64,
     lendee_id: i64,
     lent_on: LendingDate,
     due: DueDate,
+    operation_id: Option<OperationId>,
+    device_id: Option<i64>,
 }
 
 #[tracing::instrument(skip(db), err(Debug))]
-async fn save_lending(lending: &DbLending, db: &Database) -> crate::Result<()> {
-    sqlx::query(
+async fn lend_replayed(
+    operation_id: OperationId,
+    library_id: i64,
+    db: &Database,
+) -> crate::Result<bool> {
+    sqlx::query_as(
+        "
+        select from lendings l
+        join copies c on c.id = l.copy_id
+        join books b on b.id = c.book_id
+        where l.lend_operation_id = $1
+          and b.library_id = $2;
+        ",
+    )
+    .bind(operation_id)
+    .bind(library_id)
+    .fetch_optional(db)
+    .await
+    .map(|row: Option<()>| row.is_some())
+    .map_err(Error::from)
+}
+
+#[tracing::instrument(skip(tx), err(Debug))]
+async fn find_copy(
+    library_id: i64,
+    lent: Lent,
+    tx: &mut Transaction<'_>,
+) -> crate::Result<Option<DbCopy>> {
+    let (book_id, copy_id) = match lent {
+        Lent::Book(id) => (Some(id), None),
+        Lent::Copy(id) => (None, Some(id)),
+    };
+    sqlx::query_as(
+        "
+        select c.id, c.book_id,
+          exists (
+            select from lendings l
+            where l.copy_id = c.id
+              and l.returned_on is null
+          ) as lent
+        from copies c
+        join books b on b.id = c.book_id
+        where (c.book_id = $1 or c.id = $2)
+          and b.library_id = $3
+        order by lent, c.id
+        limit 1;
+        ",
+    )
+    .bind(book_id)
+    .bind(copy_id)
+    .bind(library_id)
+    .fetch_optional(&mut **tx)
+    .await
+    .map_err(Error::from)
+}
+
+/// Returns `false` when a lending with the same operation id already exists.
+#[tracing::instrument(skip(tx))]
+async fn save_lending(
+    lending: &DbLending,
+    tx: &mut Transaction<'_>,
+) -> crate::Result<bool> {
+    match sqlx::query(
         "
         insert into lendings
-          (book_id, lendee_id, lent_on, due)
+          (copy_id, lendee_id, lent_on, due, lend_operation_id, device_id)
         values
-          ($1, $2, $3, $4);
+          ($1, $2, $3, $4, $5, $6);
         ",
     )
-    .bind(lending.book_id)
+    .bind(lending.copy_id)
     .bind(lending.lendee_id)
     .bind(&lending.lent_on)
     .bind(&lending.due)
-    .execute(db)
+    .bind(lending.operation_id)
+    .bind(lending.device_id)
+    .execute(&mut **tx)
     .await
-    .map(|_| ())
-    .map_err(Error::from)
+    {
+        Err(e) if error_kind(&e) == Some(ErrorKind::UniqueViolation) => {
+            match error_constraint(&e) {
+                Some("lendings_lend_operation_id_key") => Ok(false),
+                _ => Err(Error::AlreadyLent).inspect_err(telemetry::debug),
+            }
+        }
+        other => other
+            .map(|_| true)
+            .map_err(Error::from)
+            .inspect_err(telemetry::error),
+    }
 }
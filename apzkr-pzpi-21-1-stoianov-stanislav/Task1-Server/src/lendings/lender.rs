@@ -0,0 +1,26 @@
+use crate::{
+    books::check_owns, devices::check_device, state::AppState, telemetry, Error,
+};
+
+use super::Lender;
+
+pub async fn authorize_lender(
+    lender: Lender,
+    library_id: i64,
+    state: &AppState,
+) -> crate::Result<Option<i64>> {
+    match lender {
+        Lender::Owner(owner_id) => {
+            let owner_id = owner_id
+                .sql_id(&state.id_cipher)
+                .map_err(|_| Error::LoggedOff)
+                .inspect_err(telemetry::debug)?;
+            check_owns(owner_id, library_id, &state.database)
+                .await
+                .map(|_| None)
+        }
+        Lender::Device(device_id) => {
+            check_device(device_id, library_id, state).await.map(Some)
+        }
+    }
+}
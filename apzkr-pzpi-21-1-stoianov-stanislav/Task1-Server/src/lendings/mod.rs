@@ -1,37 +1,65 @@
+mod charge;
 mod due_date;
 mod lending_date;
 mod return_date;
 
 mod active;
+mod borrowed;
+mod export;
 mod lend;
+mod lender;
+mod renew;
 mod returns;
 
 pub use active::active_lendings;
+pub use borrowed::my_lendings;
+pub use export::export_lendings;
 pub use lend::lend_book;
+pub use renew::renew_lending;
 pub use returns::return_book;
 
+use chrono::NaiveDate;
 use serde::{Deserialize, Serialize};
+use uuid::Uuid;
 
 use crate::{
-    auth::{User, UserId},
-    books::{Book, BookId},
+    auth::{Email, User, UserId},
+    books::{self, Book, BookId},
+    copies::{Barcode, CopyId},
+    devices::DeviceId,
+    export::Columns,
     id::{tag, Id},
+    libraries::{self, LibraryId},
 };
 
 pub use self::{
+    charge::Charge,
     due_date::{DueDate, LentFor},
     lending_date::{LendingDate, UnvalidatedLendingDate},
+    return_date::{ReturnDate, UnvalidatedReturnDate},
 };
 
+use self::charge::Fee;
+
 pub type LendingId = Id<{ tag("lending") }>;
 
+pub type OperationId = Uuid;
+
+#[derive(Clone, Copy, Debug)]
+pub enum Lender {
+    Owner(UserId),
+    Device(DeviceId),
+}
+
 #[derive(Clone, Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct NewLending {
     pub lendee_id: UserId,
-    pub book_id: BookId,
+    pub book_id: Option<BookId>,
+    pub copy_id: Option<CopyId>,
     pub lent_on: UnvalidatedLendingDate,
     pub lent_for: LentFor,
+    pub operation_id: Option<OperationId>,
 }
 
 #[derive(Clone, Debug, Serialize)]
@@ -39,13 +67,122 @@ pub struct NewLending {
 pub struct Lending {
     pub id: LendingId,
     pub book: Book,
+    pub copy_id: CopyId,
+    pub barcode: Barcode,
+    pub library_id: LibraryId,
+    pub library_name: libraries::Name,
     pub lendee: User,
     pub lent_on: LendingDate,
     pub due: DueDate,
+    pub returned_on: Option<ReturnDate>,
+    pub renewals: i16,
+    pub charge: Charge,
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum LendingSort {
+    #[default]
+    Due,
+    LentOn,
+}
+
+impl LendingSort {
+    pub fn column(self) -> &'static str {
+        match self {
+            Self::Due => "due",
+            Self::LentOn => "lent_on",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum LendingStatus {
+    Active,
+    Returned,
 }
 
 #[derive(Clone, Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub struct ReturnRequest {
+pub struct LendingFilter {
+    pub status: Option<LendingStatus>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActiveLendingFilter {
+    #[serde(default)]
+    pub overdue: bool,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LendingPeriod {
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportedLending {
+    pub id: LendingId,
     pub book_id: BookId,
+    pub book_name: books::Name,
+    pub copy_id: CopyId,
+    pub barcode: Barcode,
+    pub lendee_id: UserId,
+    pub lendee_email: Email,
+    pub lent_on: LendingDate,
+    pub due: DueDate,
+    pub returned_on: Option<ReturnDate>,
+    pub renewals: i16,
+    pub lending_fee: Option<Fee>,
+    pub overdue_fee: Option<Fee>,
+    pub device_id: Option<DeviceId>,
+}
+
+impl Columns for ExportedLending {
+    const COLUMNS: &'static [&'static str] = &[
+        "id",
+        "bookId",
+        "bookName",
+        "copyId",
+        "barcode",
+        "lendeeId",
+        "lendeeEmail",
+        "lentOn",
+        "due",
+        "returnedOn",
+        "renewals",
+        "lendingFee",
+        "overdueFee",
+        "deviceId",
+    ];
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReturnRequest {
+    pub book_id: Option<BookId>,
+    pub copy_id: Option<CopyId>,
+    pub lending_id: Option<LendingId>,
+    pub returned_on: Option<UnvalidatedReturnDate>,
+    pub operation_id: Option<OperationId>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReturnedLending {
+    pub id: LendingId,
+    pub returned_on: ReturnDate,
+    pub charge: Charge,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RenewedLending {
+    pub id: LendingId,
+    pub due: DueDate,
+    pub renewals: i16,
 }
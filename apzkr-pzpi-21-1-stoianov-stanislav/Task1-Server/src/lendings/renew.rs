@@ -0,0 +1,152 @@
+use crate::{
+    auth::UserId,
+    database::{Database, Transaction},
+    libraries::{MaxRenewals, RenewalPeriod},
+    state::AppState,
+    telemetry, Error,
+};
+
+use super::{
+    due_date::DueDate, return_date::ReturnDate, LendingId, RenewedLending,
+};
+
+#[tracing::instrument(skip(state))]
+pub async fn renew_lending(
+    user_id: UserId,
+    lending_id: LendingId,
+    state: AppState,
+) -> crate::Result<RenewedLending> {
+    let user_id = user_id
+        .sql_id(&state.id_cipher)
+        .map_err(|_| Error::LoggedOff)
+        .inspect_err(telemetry::debug)?;
+    let lending_id = lending_id
+        .sql_id(&state.id_cipher)
+        .map_err(|_| Error::NotFound)
+        .inspect_err(telemetry::debug)?;
+    let lending = get_lending(lending_id, &state.database)
+        .await?
+        .ok_or(Error::NotFound)
+        .inspect_err(telemetry::debug)?;
+    if user_id != lending.lendee_id && user_id != lending.owner_id {
+        return Err(Error::Unauthorized).inspect_err(telemetry::debug);
+    }
+    if lending.returned_on.is_some() {
+        return Err(Error::NotRenewable("book is already returned"))
+            .inspect_err(telemetry::debug);
+    }
+    if lending.due.is_overdue(&ReturnDate::today()) {
+        return Err(Error::NotRenewable("lending is overdue"))
+            .inspect_err(telemetry::debug);
+    }
+    if lending.renewals >= i16::from(lending.max_renewals) {
+        return Err(Error::NotRenewable("renewal limit reached"))
+            .inspect_err(telemetry::debug);
+    }
+    let renewal = Renewal {
+        lending_id,
+        new_due: lending.due.extend(lending.renewal_period),
+        previous_due: lending.due,
+    };
+    let mut tx = state.database.begin().await?;
+    extend_due_date(&renewal, &mut tx).await?;
+    save_renewal(&renewal, &mut tx).await?;
+    tx.commit().await?;
+    Ok(RenewedLending {
+        id: LendingId::new(lending_id, &state.id_cipher),
+        due: renewal.new_due,
+        renewals: lending.renewals + 1,
+    })
+}
+
+#[derive(Clone, Debug, sqlx::FromRow)]
+struct DbLending {
+    lendee_id: i64,
+    owner_id: i64,
+    due: DueDate,
+    returned_on: Option<ReturnDate>,
+    renewals: i16,
+    renewal_period: RenewalPeriod,
+    max_renewals: MaxRenewals,
+}
+
+#[derive(Clone, Debug)]
+struct Renewal {
+    lending_id: i64,
+    previous_due: DueDate,
+    new_due: DueDate,
+}
+
+#[tracing::instrument(skip(db), err(Debug))]
+async fn get_lending(
+    lending_id: i64,
+    db: &Database,
+) -> crate::Result<Option<DbLending>> {
+    sqlx::query_as(
+        "
+        select l.lendee_id, lib.owner_id, l.due, l.returned_on, l.renewals,
+          lib.renewal_period, lib.max_renewals
+        from lendings l
+        join copies c on c.id = l.copy_id
+        join books b on b.id = c.book_id
+        join libraries lib on lib.id = b.library_id
+        where l.id = $1;
+        ",
+    )
+    .bind(lending_id)
+    .fetch_optional(db)
+    .await
+    .map_err(Error::from)
+}
+
+#[tracing::instrument(skip(tx))]
+async fn extend_due_date(
+    renewal: &Renewal,
+    tx: &mut Transaction<'_>,
+) -> crate::Result<()> {
+    match sqlx::query(
+        "
+        update lendings
+        set (due, renewals) = ($1, renewals + 1)
+        where id = $2
+          and due = $3
+          and returned_on is null;
+        ",
+    )
+    .bind(&renewal.new_due)
+    .bind(renewal.lending_id)
+    .bind(&renewal.previous_due)
+    .execute(&mut **tx)
+    .await
+    .map_err(Error::from)
+    .inspect_err(telemetry::error)?
+    .rows_affected()
+    {
+        0 => Err(Error::NotRenewable("lending was changed concurrently")),
+        1 => Ok(()),
+        _ => unreachable!(),
+    }
+    .inspect_err(telemetry::debug)
+}
+
+#[tracing::instrument(skip(tx), err(Debug))]
+async fn save_renewal(
+    renewal: &Renewal,
+    tx: &mut Transaction<'_>,
+) -> crate::Result<()> {
+    sqlx::query(
+        "
+        insert into lending_renewals
+          (lending_id, renewed_on, previous_due, new_due)
+        values
+          ($1, now(), $2, $3);
+        ",
+    )
+    .bind(renewal.lending_id)
+    .bind(&renewal.previous_due)
+    .bind(&renewal.new_due)
+    .execute(&mut **tx)
+    .await
+    .map(|_| ())
+    .map_err(Error::from)
+}
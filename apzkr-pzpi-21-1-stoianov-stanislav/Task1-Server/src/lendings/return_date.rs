@@ -1,13 +1,31 @@
 use chrono::{Local, NaiveDate};
 use serde::Serialize;
 
+use crate::Error;
+
+pub type UnvalidatedReturnDate = NaiveDate;
+
 #[derive(Clone, Debug, Default, sqlx::Type, Serialize)]
 #[sqlx(transparent)]
 #[serde(transparent)]
-pub struct ReturnDate(NaiveDate);
+pub struct ReturnDate(UnvalidatedReturnDate);
 
 impl ReturnDate {
+    pub fn new(date: UnvalidatedReturnDate) -> crate::Result<Self> {
+        if date > Local::now().date_naive() {
+            Err(Error::Validation("cannot return books in the future"))
+        } else {
+            Ok(Self(date))
+        }
+    }
+
     pub fn today() -> Self {
         Self(Local::now().date_naive())
     }
 }
+
+impl From<ReturnDate> for UnvalidatedReturnDate {
+    fn from(value: ReturnDate) -> Self {
+        value.0
+    }
+}
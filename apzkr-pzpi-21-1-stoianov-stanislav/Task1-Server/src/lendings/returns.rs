@@ -1,38 +1,296 @@
-use crate::{database::Database, state::AppState, telemetry, Error};
+use chrono::NaiveDate;
 
-use super::{return_date::ReturnDate, ReturnRequest};
+use crate::{
+    database::{error_constraint, Database, Transaction},
+    holds::promote_next_hold,
+    ledger::record_lending_charge,
+    libraries::{Currency, LibraryId},
+    state::AppState,
+    telemetry, Error,
+};
+
+use super::{
+    charge::{Charge, Fee, Rates},
+    due_date::DueDate,
+    lender::authorize_lender,
+    lending_date::LendingDate,
+    return_date::ReturnDate,
+    Lender, LendingId, OperationId, ReturnRequest, ReturnedLending,
+};
 
 #[tracing::instrument(skip(state))]
 pub async fn return_book(
+    lender: Lender,
+    library_id: LibraryId,
     return_request: ReturnRequest,
     state: AppState,
-) -> crate::Result<()> {
-    let book_id = return_request
-        .book_id
+) -> crate::Result<ReturnedLending> {
+    let library_id = library_id
         .sql_id(&state.id_cipher)
         .map_err(|_| Error::NotFound)
         .inspect_err(telemetry::debug)?;
-    let today = ReturnDate::today();
-    set_return_date(book_id, today, &state.database).await
+    authorize_lender(lender, library_id, &state).await?;
+    let operation_id = return_request.operation_id;
+    if let Some(returned) =
+        replayed_return(operation_id, library_id, &state).await?
+    {
+        return Ok(returned);
+    }
+    let returned_on = match return_request.returned_on {
+        Some(date) => ReturnDate::new(date)?,
+        None => ReturnDate::today(),
+    };
+    let returned = match (
+        return_request.book_id,
+        return_request.copy_id,
+        return_request.lending_id,
+    ) {
+        (Some(book_id), None, None) => {
+            book_id.sql_id(&state.id_cipher).map(Returned::Book)
+        }
+        (None, Some(copy_id), None) => {
+            copy_id.sql_id(&state.id_cipher).map(Returned::Copy)
+        }
+        (None, None, Some(lending_id)) => {
+            lending_id.sql_id(&state.id_cipher).map(Returned::Lending)
+        }
+        _ => {
+            return Err(Error::Validation(
+                "specify either a book, a copy or a lending to return",
+            ))
+        }
+    }
+    .map_err(|_| Error::NotFound)
+    .inspect_err(telemetry::debug)?;
+    let lending = match &get_open_lendings(
+        library_id,
+        returned,
+        &state.database,
+    )
+    .await?[..]
+    {
+        [] => {
+            return replayed_or(
+                Error::NotFound,
+                operation_id,
+                library_id,
+                &state,
+            )
+            .await
+        }
+        [lending] => Ok(lending.clone()),
+        _ => Err(Error::Validation(
+            "several copies of the book are lent, specify a copy or a lending",
+        )),
+    }
+    .inspect_err(telemetry::debug)?;
+    if NaiveDate::from(returned_on.clone())
+        < NaiveDate::from(lending.lent_on.clone())
+    {
+        return Err(Error::Validation(
+            "cannot return a book before lending it",
+        ))
+        .inspect_err(telemetry::debug);
+    }
+    let charge = Charge::new(
+        lending.lent_on,
+        lending.due,
+        returned_on.clone(),
+        lending.rates,
+    );
+    let mut tx = state.database.begin().await?;
+    if !set_return_date(
+        lending.id,
+        &returned_on,
+        &charge,
+        operation_id,
+        &mut tx,
+    )
+    .await?
+    {
+        return replayed_or(Error::NotFound, operation_id, library_id, &state)
+            .await;
+    }
+    record_lending_charge(
+        lending.lendee_id,
+        library_id,
+        lending.id,
+        &charge,
+        &mut tx,
+    )
+    .await?;
+    promote_next_hold(lending.book_id, &mut tx).await?;
+    tx.commit().await?;
+    Ok(ReturnedLending {
+        id: LendingId::new(lending.id, &state.id_cipher),
+        returned_on,
+        charge,
+    })
 }
 
-#[tracing::instrument(skip(db), err(Debug))]
-async fn set_return_date(
+/// A concurrent replay of the same operation may have returned the lending
+/// first, in which case its result is returned instead of the error.
+async fn replayed_or(
+    error: Error,
+    operation_id: Option<OperationId>,
+    library_id: i64,
+    state: &AppState,
+) -> crate::Result<ReturnedLending> {
+    replayed_return(operation_id, library_id, state)
+        .await?
+        .ok_or(error)
+        .inspect_err(telemetry::debug)
+}
+
+async fn replayed_return(
+    operation_id: Option<OperationId>,
+    library_id: i64,
+    state: &AppState,
+) -> crate::Result<Option<ReturnedLending>> {
+    let Some(operation_id) = operation_id else {
+        return Ok(None);
+    };
+    get_replayed_return(operation_id, library_id, &state.database)
+        .await
+        .map(|replayed| {
+            replayed.map(|replayed| ReturnedLending {
+                id: LendingId::new(replayed.id, &state.id_cipher),
+                returned_on: replayed.returned_on,
+                charge: Charge::from_fees(
+                    replayed.lending_fee,
+                    replayed.overdue_fee,
+                    replayed.currency,
+                ),
+            })
+        })
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Returned {
+    Book(i64),
+    Copy(i64),
+    Lending(i64),
+}
+
+#[derive(Clone, Debug, sqlx::FromRow)]
+struct OpenLending {
+    id: i64,
     book_id: i64,
-    return_date: ReturnDate,
+    lendee_id: i64,
+    lent_on: LendingDate,
+    due: DueDate,
+    #[sqlx(flatten)]
+    rates: Rates,
+}
+
+#[derive(Clone, Debug, sqlx::FromRow)]
+struct ReplayedReturn {
+    id: i64,
+    returned_on: ReturnDate,
+    lending_fee: Fee,
+    overdue_fee: Fee,
+    currency: Currency,
+}
+
+#[tracing::instrument(skip(db), err(Debug))]
+async fn get_replayed_return(
+    operation_id: OperationId,
+    library_id: i64,
     db: &Database,
-) -> crate::Result<()> {
-    sqlx::query(
+) -> crate::Result<Option<ReplayedReturn>> {
+    sqlx::query_as(
         "
-        update lendings
-        set returned_on = $1
-        where book_id = $2;
+        select l.id, l.returned_on, l.lending_fee, l.overdue_fee,
+          lib.currency
+        from lendings l
+        join copies c on c.id = l.copy_id
+        join books b on b.id = c.book_id
+        join libraries lib on lib.id = b.library_id
+        where l.return_operation_id = $1
+          and lib.id = $2;
+        ",
+    )
+    .bind(operation_id)
+    .bind(library_id)
+    .fetch_optional(db)
+    .await
+    .map_err(Error::from)
+}
+
+#[tracing::instrument(skip(db), err(Debug))]
+async fn get_open_lendings(
+    library_id: i64,
+    returned: Returned,
+    db: &Database,
+) -> crate::Result<Vec<OpenLending>> {
+    let (book_id, copy_id, lending_id) = match returned {
+        Returned::Book(id) => (Some(id), None, None),
+        Returned::Copy(id) => (None, Some(id), None),
+        Returned::Lending(id) => (None, None, Some(id)),
+    };
+    sqlx::query_as(
+        "
+        select l.id, c.book_id, l.lendee_id, l.lent_on, l.due,
+          lib.daily_rate, lib.overdue_rate, lib.currency
+        from lendings l
+        join copies c on c.id = l.copy_id
+        join books b on b.id = c.book_id
+        join libraries lib on lib.id = b.library_id
+        where (c.book_id = $1 or l.copy_id = $2 or l.id = $3)
+          and l.returned_on is null
+          and lib.id = $4;
         ",
     )
-    .bind(&return_date)
     .bind(book_id)
-    .execute(db)
+    .bind(copy_id)
+    .bind(lending_id)
+    .bind(library_id)
+    .fetch_all(db)
     .await
-    .map(|_| ())
     .map_err(Error::from)
 }
+
+/// Returns `false` when the lending is already returned or the operation id
+/// is taken.
+#[tracing::instrument(skip(tx))]
+async fn set_return_date(
+    lending_id: i64,
+    return_date: &ReturnDate,
+    charge: &Charge,
+    operation_id: Option<OperationId>,
+    tx: &mut Transaction<'_>,
+) -> crate::Result<bool> {
+    match sqlx::query(
+        "
+        update lendings
+        set (returned_on, lending_fee, overdue_fee, return_operation_id)
+          = ($1, $2, $3, $4)
+        where id = $5
+          and returned_on is null;
+        ",
+    )
+    .bind(return_date)
+    .bind(charge.lending_fee)
+    .bind(charge.overdue_fee)
+    .bind(operation_id)
+    .bind(lending_id)
+    .execute(&mut **tx)
+    .await
+    {
+        Err(e)
+            if error_constraint(&e)
+                == Some("lendings_return_operation_id_key") =>
+        {
+            Ok(false)
+        }
+        other => match other
+            .map_err(Error::from)
+            .inspect_err(telemetry::error)?
+            .rows_affected()
+        {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => unreachable!(),
+        },
+    }
+}
@@ -5,10 +5,17 @@ pub mod telemetry;
 
 mod database;
 mod error;
+mod export;
 mod id;
+mod idempotency;
+mod page;
 
 mod auth;
 mod books;
+mod copies;
+mod devices;
+mod holds;
+mod ledger;
 mod lendings;
 mod libraries;
 mod backup;
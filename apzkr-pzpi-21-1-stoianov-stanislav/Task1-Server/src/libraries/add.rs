@@ -6,8 +6,9 @@ use crate::{
 };
 
 use super::{
-    address::Address, currency::Currency, daily_rate::DailyRate, name::Name,
-    overdue_rate::OverdueRate, NewLibrary,
+    address::Address, balance_limit::BalanceLimit, currency::Currency,
+    daily_rate::DailyRate, max_renewals::MaxRenewals, name::Name,
+    overdue_rate::OverdueRate, renewal_period::RenewalPeriod, NewLibrary,
 };
 
 #[tracing::instrument(skip(state))]
@@ -32,6 +33,20 @@ pub async fn add_library(
         daily_rate: DailyRate::new(library.daily_rate)?,
         overdue_rate: OverdueRate::new(library.overdue_rate)?,
         currency: Currency::new(library.currency)?,
+        balance_limit: library
+            .balance_limit
+            .map(BalanceLimit::new)
+            .transpose()?,
+        renewal_period: library
+            .renewal_period
+            .map(RenewalPeriod::new)
+            .transpose()?
+            .unwrap_or_default(),
+        max_renewals: library
+            .max_renewals
+            .map(MaxRenewals::new)
+            .transpose()?
+            .unwrap_or_default(),
     };
     create_library(&library, &state.database).await
 }
@@ -44,6 +59,9 @@ struct CreateLibrary {
     daily_rate: DailyRate,
     overdue_rate: OverdueRate,
     currency: Currency,
+    balance_limit: Option<BalanceLimit>,
+    renewal_period: RenewalPeriod,
+    max_renewals: MaxRenewals,
 }
 
 #[tracing::instrument(skip(db), err(Debug))]
@@ -54,9 +72,10 @@ async fn create_library(
     sqlx::query(
         "
         insert into libraries
-          (name, address, daily_rate, overdue_rate, currency, owner_id)
+          (name, address, daily_rate, overdue_rate, currency,
+           balance_limit, renewal_period, max_renewals, owner_id)
         values
-          ($1, $2, $3, $4, $5, $6);
+          ($1, $2, $3, $4, $5, $6, $7, $8, $9);
         ",
     )
     .bind(&library.name)
@@ -64,6 +83,9 @@ async fn create_library(
     .bind(&library.daily_rate)
     .bind(&library.overdue_rate)
     .bind(&library.currency)
+    .bind(&library.balance_limit)
+    .bind(library.renewal_period)
+    .bind(library.max_renewals)
     .bind(library.owner_id)
     .execute(db)
     .await
@@ -0,0 +1,35 @@
+use serde::Serialize;
+use sqlx::types::Decimal;
+
+use crate::Error;
+
+pub type UnvalidatedBalanceLimit = Decimal;
+
+#[derive(Clone, Debug, Default, sqlx::Type, Serialize)]
+#[sqlx(transparent)]
+#[serde(transparent)]
+pub struct BalanceLimit(UnvalidatedBalanceLimit);
+
+impl BalanceLimit {
+    pub fn new(limit: UnvalidatedBalanceLimit) -> crate::Result<Self> {
+        if limit.is_sign_negative() {
+            Err(Error::Validation("balance limit cannot be negative"))
+        } else {
+            Ok(Self(limit))
+        }
+    }
+}
+
+impl TryFrom<UnvalidatedBalanceLimit> for BalanceLimit {
+    type Error = Error;
+
+    fn try_from(value: UnvalidatedBalanceLimit) -> Result<Self, Self::Error> {
+        Self::new(value)
+    }
+}
+
+impl From<BalanceLimit> for UnvalidatedBalanceLimit {
+    fn from(value: BalanceLimit) -> Self {
+        value.0
+    }
+}
@@ -0,0 +1,42 @@
+use serde::Serialize;
+
+use crate::Error;
+
+pub type UnvalidatedMaxRenewals = i16;
+
+#[derive(Clone, Copy, Debug, sqlx::Type, Serialize)]
+#[sqlx(transparent)]
+#[serde(transparent)]
+pub struct MaxRenewals(UnvalidatedMaxRenewals);
+
+impl MaxRenewals {
+    pub fn new(renewals: UnvalidatedMaxRenewals) -> crate::Result<Self> {
+        match renewals {
+            r if r < 0 => {
+                Err(Error::Validation("renewal limit cannot be negative"))
+            }
+            r if r > 100 => Err(Error::Validation("renewal limit is too high")),
+            r => Ok(Self(r)),
+        }
+    }
+}
+
+impl Default for MaxRenewals {
+    fn default() -> Self {
+        Self(2)
+    }
+}
+
+impl TryFrom<UnvalidatedMaxRenewals> for MaxRenewals {
+    type Error = Error;
+
+    fn try_from(value: UnvalidatedMaxRenewals) -> Result<Self, Self::Error> {
+        Self::new(value)
+    }
+}
+
+impl From<MaxRenewals> for UnvalidatedMaxRenewals {
+    fn from(value: MaxRenewals) -> Self {
+        value.0
+    }
+}
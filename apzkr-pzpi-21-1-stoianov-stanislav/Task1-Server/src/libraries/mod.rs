@@ -1,9 +1,12 @@
 mod address;
-mod rating;
+mod balance_limit;
 mod currency;
 mod daily_rate;
+mod max_renewals;
 mod name;
 mod overdue_rate;
+mod rating;
+mod renewal_period;
 
 mod add;
 mod delete;
@@ -16,22 +19,35 @@ pub use update::update_library;
 pub use view::{list_libraries, list_my_libraries, view_library};
 
 use serde::{Deserialize, Serialize};
+use serde_with::{serde_as, NoneAsEmptyString};
 
 use crate::{
     auth::UserId,
     id::{tag, Id},
 };
 
-use self::{
-    address::{Address, UnvalidatedAddress},
+pub use self::{
+    balance_limit::BalanceLimit,
     currency::{Currency, UnvalidatedCurrency},
     daily_rate::{DailyRate, UnvalidatedDailyRate},
-    name::{Name, UnvalidatedName},
-    overdue_rate::{OverdueRate, UnvalidatedOverdueRate}, rating::Rating,
+    max_renewals::MaxRenewals,
+    name::Name,
+    overdue_rate::{OverdueRate, UnvalidatedOverdueRate},
+    renewal_period::RenewalPeriod,
+};
+
+use self::{
+    address::{Address, UnvalidatedAddress},
+    balance_limit::UnvalidatedBalanceLimit,
+    max_renewals::UnvalidatedMaxRenewals,
+    name::UnvalidatedName,
+    rating::Rating,
+    renewal_period::UnvalidatedRenewalPeriod,
 };
 
 pub type LibraryId = Id<{ tag("library") }>;
 
+#[serde_as]
 #[derive(Clone, Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct NewLibrary {
@@ -41,6 +57,15 @@ pub struct NewLibrary {
     pub daily_rate: UnvalidatedDailyRate,
     pub overdue_rate: UnvalidatedOverdueRate,
     pub currency: UnvalidatedCurrency,
+    #[serde_as(as = "NoneAsEmptyString")]
+    #[serde(default)]
+    pub balance_limit: Option<UnvalidatedBalanceLimit>,
+    #[serde_as(as = "NoneAsEmptyString")]
+    #[serde(default)]
+    pub renewal_period: Option<UnvalidatedRenewalPeriod>,
+    #[serde_as(as = "NoneAsEmptyString")]
+    #[serde(default)]
+    pub max_renewals: Option<UnvalidatedMaxRenewals>,
 }
 
 #[derive(Clone, Debug, Serialize)]
@@ -52,6 +77,27 @@ pub struct Library {
     pub daily_rate: DailyRate,
     pub overdue_rate: OverdueRate,
     pub currency: Currency,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub balance_limit: Option<BalanceLimit>,
+    pub renewal_period: RenewalPeriod,
+    pub max_renewals: MaxRenewals,
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum LibrarySort {
+    #[default]
+    Name,
+    DailyRate,
+}
+
+impl LibrarySort {
+    pub fn column(self) -> &'static str {
+        match self {
+            Self::Name => "name",
+            Self::DailyRate => "daily_rate",
+        }
+    }
 }
 
 #[derive(Clone, Debug, Serialize)]
@@ -63,11 +109,15 @@ pub struct RatedLibrary {
     pub daily_rate: DailyRate,
     pub overdue_rate: OverdueRate,
     pub currency: Currency,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub balance_limit: Option<BalanceLimit>,
+    pub renewal_period: RenewalPeriod,
+    pub max_renewals: MaxRenewals,
     pub owner_id: UserId,
-    pub rating: Rating
+    pub rating: Rating,
 }
 
-
+#[serde_as]
 #[derive(Clone, Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct UpdateLibrary {
@@ -77,4 +127,13 @@ pub struct UpdateLibrary {
     pub daily_rate: UnvalidatedDailyRate,
     pub overdue_rate: UnvalidatedOverdueRate,
     pub currency: UnvalidatedCurrency,
+    #[serde_as(as = "NoneAsEmptyString")]
+    #[serde(default)]
+    pub balance_limit: Option<UnvalidatedBalanceLimit>,
+    #[serde_as(as = "NoneAsEmptyString")]
+    #[serde(default)]
+    pub renewal_period: Option<UnvalidatedRenewalPeriod>,
+    #[serde_as(as = "NoneAsEmptyString")]
+    #[serde(default)]
+    pub max_renewals: Option<UnvalidatedMaxRenewals>,
 }
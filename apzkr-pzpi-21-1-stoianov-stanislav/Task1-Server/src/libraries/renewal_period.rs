@@ -0,0 +1,44 @@
+use serde::Serialize;
+
+use crate::Error;
+
+pub type UnvalidatedRenewalPeriod = i16;
+
+#[derive(Clone, Copy, Debug, sqlx::Type, Serialize)]
+#[sqlx(transparent)]
+#[serde(transparent)]
+pub struct RenewalPeriod(UnvalidatedRenewalPeriod);
+
+impl RenewalPeriod {
+    pub fn new(days: UnvalidatedRenewalPeriod) -> crate::Result<Self> {
+        match days {
+            d if d < 1 => {
+                Err(Error::Validation("renewal period must be at least a day"))
+            }
+            d if d > 365 => {
+                Err(Error::Validation("renewal period cannot exceed a year"))
+            }
+            d => Ok(Self(d)),
+        }
+    }
+}
+
+impl Default for RenewalPeriod {
+    fn default() -> Self {
+        Self(14)
+    }
+}
+
+impl TryFrom<UnvalidatedRenewalPeriod> for RenewalPeriod {
+    type Error = Error;
+
+    fn try_from(value: UnvalidatedRenewalPeriod) -> Result<Self, Self::Error> {
+        Self::new(value)
+    }
+}
+
+impl From<RenewalPeriod> for UnvalidatedRenewalPeriod {
+    fn from(value: RenewalPeriod) -> Self {
+        value.0
+    }
+}
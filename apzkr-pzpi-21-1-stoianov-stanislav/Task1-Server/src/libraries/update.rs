@@ -6,8 +6,10 @@ use crate::{
 };
 
 use super::{
-    address::Address, currency::Currency, daily_rate::DailyRate, name::Name,
-    overdue_rate::OverdueRate, LibraryId, UpdateLibrary,
+    address::Address, balance_limit::BalanceLimit, currency::Currency,
+    daily_rate::DailyRate, max_renewals::MaxRenewals, name::Name,
+    overdue_rate::OverdueRate, renewal_period::RenewalPeriod, LibraryId,
+    UpdateLibrary,
 };
 
 #[tracing::instrument(skip(state))]
@@ -39,6 +41,15 @@ pub async fn update_library(
         daily_rate: DailyRate::new(library.daily_rate)?,
         overdue_rate: OverdueRate::new(library.overdue_rate)?,
         currency: Currency::new(library.currency)?,
+        balance_limit: library
+            .balance_limit
+            .map(BalanceLimit::new)
+            .transpose()?,
+        renewal_period: library
+            .renewal_period
+            .map(RenewalPeriod::new)
+            .transpose()?,
+        max_renewals: library.max_renewals.map(MaxRenewals::new).transpose()?,
     };
     update_db_library(&library, &state.database).await
 }
@@ -52,6 +63,9 @@ struct DbLibrary {
     daily_rate: DailyRate,
     overdue_rate: OverdueRate,
     currency: Currency,
+    balance_limit: Option<BalanceLimit>,
+    renewal_period: Option<RenewalPeriod>,
+    max_renewals: Option<MaxRenewals>,
 }
 
 #[tracing::instrument(skip(db))]
@@ -62,9 +76,11 @@ async fn update_db_library(
     match sqlx::query(
         "
         update libraries
-        set (name, address, daily_rate, overdue_rate, currency, owner_id)
-          = ($1, $2, $3, $4, $5, $6)
-        where id = $7;
+        set (name, address, daily_rate, overdue_rate, currency,
+             balance_limit, renewal_period, max_renewals, owner_id)
+          = ($1, $2, $3, $4, $5, coalesce($6, balance_limit),
+             coalesce($7, renewal_period), coalesce($8, max_renewals), $9)
+        where id = $10;
         ",
     )
     .bind(&library.name)
@@ -72,6 +88,9 @@ async fn update_db_library(
     .bind(&library.daily_rate)
     .bind(&library.overdue_rate)
     .bind(&library.currency)
+    .bind(&library.balance_limit)
+    .bind(library.renewal_period)
+    .bind(library.max_renewals)
     .bind(library.owner_id)
     .bind(library.id)
     .execute(db)
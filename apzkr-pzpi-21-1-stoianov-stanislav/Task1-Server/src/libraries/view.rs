@@ -2,30 +2,45 @@ use crate::{
     auth::UserId,
     database::Database,
     lendings::{DueDate, LendingDate},
+    page::{check_cursor, Page, PageRequest},
     state::AppState,
     telemetry, Error,
 };
 
 use super::{
-    address::Address, currency::Currency, daily_rate::DailyRate, name::Name,
-    overdue_rate::OverdueRate, Library, LibraryId, RatedLibrary,
+    address::Address, balance_limit::BalanceLimit, currency::Currency,
+    daily_rate::DailyRate, max_renewals::MaxRenewals, name::Name,
+    overdue_rate::OverdueRate, renewal_period::RenewalPeriod, Library,
+    LibraryId, LibrarySort, RatedLibrary,
 };
 
 #[tracing::instrument(skip(state))]
-pub async fn list_libraries(state: AppState) -> crate::Result<Vec<Library>> {
-    get_all_libraries(&state.database).await.map(|libraries| {
-        libraries
-            .into_iter()
-            .map(|library| Library {
-                id: LibraryId::new(library.id, &state.id_cipher),
-                name: library.name,
-                address: library.address,
-                daily_rate: library.daily_rate,
-                overdue_rate: library.overdue_rate,
-                currency: library.currency,
-            })
-            .collect()
-    })
+pub async fn list_libraries(
+    page: PageRequest<LibraryId, LibrarySort>,
+    state: AppState,
+) -> crate::Result<Page<Library, LibraryId>> {
+    let cursor = page.sql_cursor(&state.id_cipher)?;
+    check_cursor("libraries", cursor, &state.database).await?;
+    let limit = page.limit();
+    get_libraries_page(cursor, limit, page.sort, &state.database)
+        .await
+        .map(|libraries| {
+            let libraries = libraries
+                .into_iter()
+                .map(|library| Library {
+                    id: LibraryId::new(library.id, &state.id_cipher),
+                    name: library.name,
+                    address: library.address,
+                    daily_rate: library.daily_rate,
+                    overdue_rate: library.overdue_rate,
+                    currency: library.currency,
+                    balance_limit: library.balance_limit,
+                    renewal_period: library.renewal_period,
+                    max_renewals: library.max_renewals,
+                })
+                .collect();
+            Page::new(libraries, limit, |library| library.id)
+        })
 }
 
 #[tracing::instrument(skip(state))]
@@ -49,6 +64,9 @@ pub async fn list_my_libraries(
                     daily_rate: library.daily_rate,
                     overdue_rate: library.overdue_rate,
                     currency: library.currency,
+                    balance_limit: library.balance_limit,
+                    renewal_period: library.renewal_period,
+                    max_renewals: library.max_renewals,
                 })
                 .collect()
         })
@@ -85,6 +103,9 @@ pub async fn view_library(
         daily_rate: library.daily_rate,
         overdue_rate: library.overdue_rate,
         currency: library.currency,
+        balance_limit: library.balance_limit,
+        renewal_period: library.renewal_period,
+        max_renewals: library.max_renewals,
         owner_id,
         rating,
     })
@@ -98,6 +119,9 @@ struct DbLibrary {
     daily_rate: DailyRate,
     overdue_rate: OverdueRate,
     currency: Currency,
+    balance_limit: Option<BalanceLimit>,
+    renewal_period: RenewalPeriod,
+    max_renewals: MaxRenewals,
 }
 
 #[tracing::instrument(skip(db), err(Debug))]
@@ -117,13 +141,26 @@ async fn get_owner(library_id: i64, db: &Database) -> crate::Result<i64> {
 }
 
 #[tracing::instrument(skip(db), err(Debug))]
-async fn get_all_libraries(db: &Database) -> crate::Result<Vec<DbLibrary>> {
-    sqlx::query_as(
+async fn get_libraries_page(
+    cursor: Option<i64>,
+    limit: i64,
+    sort: LibrarySort,
+    db: &Database,
+) -> crate::Result<Vec<DbLibrary>> {
+    sqlx::query_as(&format!(
         "
-        select id, name, address, daily_rate, overdue_rate, currency
-        from libraries;
+        select id, name, address, daily_rate, overdue_rate, currency,
+          balance_limit, renewal_period, max_renewals
+        from libraries
+        where $1::bigint is null
+          or ({key}, id) > (select {key}, id from libraries where id = $1)
+        order by {key}, id
+        limit $2 + 1;
         ",
-    )
+        key = sort.column(),
+    ))
+    .bind(cursor)
+    .bind(limit)
     .fetch_all(db)
     .await
     .map_err(crate::Error::from)
@@ -136,7 +173,8 @@ async fn get_user_libraries(
 ) -> crate::Result<Vec<DbLibrary>> {
     sqlx::query_as(
         "
-        select id, name, address, daily_rate, overdue_rate, currency
+        select id, name, address, daily_rate, overdue_rate, currency,
+          balance_limit, renewal_period, max_renewals
         from libraries
         where owner_id = $1;
         ",
@@ -154,7 +192,8 @@ async fn get_library(
 ) -> crate::Result<Option<DbLibrary>> {
     sqlx::query_as(
         "
-        select id, name, address, daily_rate, overdue_rate, currency
+        select id, name, address, daily_rate, overdue_rate, currency,
+          balance_limit, renewal_period, max_renewals
         from libraries
         where id = $1;
         ",
@@ -172,11 +211,11 @@ async fn get_library_activity(
 ) -> crate::Result<Vec<i64>> {
     sqlx::query_as::<_, (LendingDate, DueDate)>(
         "
-        select lent_on, due 
-        from lendings
-        where book_id in (
-          select id from books where library_id = $1
-        );
+        select l.lent_on, l.due
+        from lendings l
+        join copies c on c.id = l.copy_id
+        join books b on b.id = c.book_id
+        where b.library_id = $1;
         ",
     )
     .bind(library_id)
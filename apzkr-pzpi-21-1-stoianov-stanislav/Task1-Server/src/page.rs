@@ -0,0 +1,83 @@
+use aes::Aes128;
+use serde::{Deserialize, Serialize};
+
+use crate::{database::Database, id::Id, telemetry, Error};
+
+const DEFAULT_LIMIT: i64 = 20;
+const MAX_LIMIT: i64 = 100;
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PageRequest<C, S> {
+    pub limit: Option<i64>,
+    pub cursor: Option<C>,
+    #[serde(default)]
+    pub sort: S,
+}
+
+impl<C, S> PageRequest<C, S> {
+    pub fn limit(&self) -> i64 {
+        self.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT)
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Page<T, C> {
+    pub items: Vec<T>,
+    pub next_cursor: Option<C>,
+}
+
+impl<T, C> Page<T, C> {
+    pub fn new(
+        mut items: Vec<T>,
+        limit: i64,
+        cursor: impl Fn(&T) -> C,
+    ) -> Self {
+        let limit = limit as usize;
+        let next_cursor = match items.len() > limit {
+            true => {
+                items.truncate(limit);
+                items.last().map(cursor)
+            }
+            false => None,
+        };
+        Self { items, next_cursor }
+    }
+}
+
+impl<const TAG: u64, S> PageRequest<Id<TAG>, S> {
+    pub fn sql_cursor(&self, cipher: &Aes128) -> crate::Result<Option<i64>> {
+        self.cursor
+            .map(|cursor| cursor.sql_id(cipher))
+            .transpose()
+            .map_err(|_| Error::Validation("cursor is invalid"))
+            .inspect_err(telemetry::debug)
+    }
+}
+
+/// Pages resume after the cursor row, so it has to still exist.
+#[tracing::instrument(skip(db))]
+pub async fn check_cursor(
+    table: &'static str,
+    cursor: Option<i64>,
+    db: &Database,
+) -> crate::Result<()> {
+    let Some(cursor) = cursor else {
+        return Ok(());
+    };
+    sqlx::query_as(&format!(
+        "
+        select from {table}
+        where id = $1;
+        ",
+    ))
+    .bind(cursor)
+    .fetch_optional(db)
+    .await
+    .map_err(Error::from)
+    .and_then(|row| {
+        row.ok_or(Error::Validation("cursor no longer exists"))
+            .inspect_err(telemetry::debug)
+    })
+}
@@ -3,7 +3,9 @@ use std::sync::Arc;
 use aes::{cipher::KeyInit, Aes128};
 
 use crate::{
-    config::{AppConfig, BackupConfig, HasherConfig, JwtConfig},
+    config::{
+        AppConfig, BackupConfig, HasherConfig, IdempotencyConfig, JwtConfig,
+    },
     database::{self, Database},
 };
 
@@ -14,6 +16,7 @@ pub struct AppState {
     pub jwt_config: Arc<JwtConfig>,
     pub hasher_config: Arc<HasherConfig>,
     pub backup_config: Arc<BackupConfig>,
+    pub idempotency_config: Arc<IdempotencyConfig>,
 }
 
 impl AppState {
@@ -24,6 +27,7 @@ impl AppState {
             jwt_config: Arc::new(config.jwt),
             hasher_config: Arc::new(config.hasher),
             backup_config: Arc::new(config.backup),
+            idempotency_config: Arc::new(config.idempotency),
         }
     }
 }
@@ -1,39 +1,102 @@
-use std::io::Write;
-use ligma as lib;
+use std::{io::Write, time::Duration};
+
+use chrono::Local;
+use ligma::{
+    Client, Credentials, Journal, Kiosk, LendRequest, Outcome, ReturnRequest,
+    SyncReport,
+};
+
+const TIMEOUT: Duration = Duration::from_secs(1);
+const JOURNAL: &str = "ligma-journal.jsonl";
+const OFFLINE_LENT_FOR: u64 = 14;
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
-    lib::set_base_url("http://localhost:8080");
-    lib::init_settings()?;
+    let (mut kiosk, mut library_id) = settings()?;
     loop {
+        match kiosk.sync().await {
+            Ok(report) => print_report(&report),
+            Err(e) => println!("Error: {e}"),
+        }
         match prompt("Enter your command:").as_str() {
-            "settings" => {
-                let url = prompt("Base URL:");
-                lib::set_base_url(&url);
-            }
+            "settings" => (kiosk, library_id) = settings()?,
             "lend" => {
                 let lendee_id = prompt("Who lends?");
                 let book_id = prompt("Which book?");
-                lib::lend_book(&lendee_id, &book_id)
-                    .await
-                    .inspect_err(|e| println!("Error: {e:?}"))
-                    .ok();
-                println!("Happy reading");
+                let lent_for =
+                    match kiosk.client().lending_score(&book_id).await {
+                        Ok(score) => score.days_to_lend(),
+                        Err(e) if e.is_offline() => OFFLINE_LENT_FOR,
+                        Err(e) => {
+                            println!("Error: {e}");
+                            continue;
+                        }
+                    };
+                let request = LendRequest {
+                    lendee_id,
+                    book_id: Some(book_id),
+                    copy_id: None,
+                    lent_on: Local::now().date_naive(),
+                    lent_for,
+                    operation_id: None,
+                };
+                match kiosk.lend_book(&library_id, request).await {
+                    Ok(Outcome::Completed(())) => println!("Happy reading"),
+                    Ok(Outcome::Queued) => {
+                        println!("Server is unreachable, the lending is saved")
+                    }
+                    Err(e) => println!("Error: {e}"),
+                }
             }
             "return" => {
-                let book_id = prompt("Which book?");
-                lib::return_book(&book_id)
-                    .await
-                    .inspect_err(|e| println!("Error: {e:?}"))
-                    .ok();
-                println!("Returned the book successfully");
+                let request = ReturnRequest {
+                    book_id: Some(prompt("Which book?")),
+                    ..Default::default()
+                };
+                match kiosk.return_book(&library_id, request).await {
+                    Ok(Outcome::Completed(returned)) => println!(
+                        "Returned the book successfully, charged {} {}",
+                        returned.charge.total, returned.charge.currency
+                    ),
+                    Ok(Outcome::Queued) => {
+                        println!("Server is unreachable, the return is saved")
+                    }
+                    Err(e) => println!("Error: {e}"),
+                }
             }
+            "sync" => {}
             "quit" => break Ok(()),
             unknown => println!("command not found: {unknown}"),
         }
     }
 }
 
+fn settings() -> anyhow::Result<(Kiosk, String)> {
+    let base_url = prompt("Base URL:");
+    let credentials = match prompt("API key (empty to sign in):") {
+        api_key if !api_key.is_empty() => Credentials::ApiKey(api_key),
+        _ => Credentials::Password {
+            email: prompt("Email:"),
+            password: prompt("Password:"),
+        },
+    };
+    let library_id = prompt("Library:");
+    let client = Client::new(base_url, TIMEOUT, credentials)?;
+    Ok((Kiosk::new(client, Journal::new(JOURNAL)), library_id))
+}
+
+fn print_report(report: &SyncReport) {
+    if report.synced > 0 {
+        println!("Synchronised {} saved operations", report.synced);
+    }
+    for (operation, e) in &report.rejected {
+        println!("Rejected saved operation {operation:?}: {e}");
+    }
+    if report.pending > 0 {
+        println!("{} operations are waiting for the server", report.pending);
+    }
+}
+
 fn prompt(prompt: &str) -> String {
     print!("{prompt} ");
     let mut buf = String::new();
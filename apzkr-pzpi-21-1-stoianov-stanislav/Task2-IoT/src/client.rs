@@ -0,0 +1,154 @@
+use std::time::Duration;
+
+use reqwest::{
+    header::{COOKIE, SET_COOKIE},
+    RequestBuilder, Response, StatusCode,
+};
+use serde::de::DeserializeOwned;
+use tokio::sync::Mutex;
+
+use crate::{
+    error::{Error, Result},
+    models::{
+        Credentials, ErrorBody, LendRequest, LendingScore, ReturnRequest,
+        ReturnedLending, SignInRequest,
+    },
+};
+
+const ACCESS_TOKEN: &str = "access-token";
+const API_KEY: &str = "x-api-key";
+
+#[derive(Debug)]
+pub struct Client {
+    http: reqwest::Client,
+    base_url: String,
+    credentials: Credentials,
+    access_token: Mutex<Option<String>>,
+}
+
+impl Client {
+    pub fn new(
+        base_url: impl Into<String>,
+        timeout: Duration,
+        credentials: Credentials,
+    ) -> Result<Self> {
+        let http = reqwest::Client::builder().timeout(timeout).build()?;
+        Ok(Self {
+            http,
+            base_url: base_url.into().trim_end_matches('/').to_owned(),
+            credentials,
+            access_token: Mutex::new(None),
+        })
+    }
+
+    pub async fn lend_book(
+        &self,
+        library_id: &str,
+        request: &LendRequest,
+    ) -> Result<()> {
+        let url = self.url(&format!("/libraries/{library_id}/lendings/new"));
+        self.send_authorized(|http| http.post(&url).form(request))
+            .await
+            .map(|_| ())
+    }
+
+    pub async fn return_book(
+        &self,
+        library_id: &str,
+        request: &ReturnRequest,
+    ) -> Result<ReturnedLending> {
+        let url = self.url(&format!("/libraries/{library_id}/lendings/return"));
+        let response = self
+            .send_authorized(|http| http.post(&url).form(request))
+            .await?;
+        decode(response).await
+    }
+
+    pub async fn lending_score(&self, book_id: &str) -> Result<LendingScore> {
+        let url = self.url(&format!("/books/{book_id}/lending-score"));
+        let response = check(self.http.get(&url).send().await?).await?;
+        decode(response).await
+    }
+
+    pub async fn sign_in(&self) -> Result<()> {
+        let token = match &self.credentials {
+            Credentials::ApiKey(_) => return Ok(()),
+            Credentials::Password { email, password } => {
+                let request = SignInRequest { email, password };
+                let response = self
+                    .http
+                    .post(self.url("/auth/sign-in"))
+                    .form(&request)
+                    .send()
+                    .await?;
+                access_token(&check(response).await?)?
+            }
+        };
+        *self.access_token.lock().await = Some(token);
+        Ok(())
+    }
+
+    async fn send_authorized(
+        &self,
+        request: impl Fn(&reqwest::Client) -> RequestBuilder,
+    ) -> Result<Response> {
+        if let Credentials::ApiKey(api_key) = &self.credentials {
+            let response =
+                request(&self.http).header(API_KEY, api_key).send().await?;
+            return check(response).await;
+        }
+        if self.access_token.lock().await.is_none() {
+            self.sign_in().await?;
+        }
+        let response = self.authorize(request(&self.http)).await.send().await?;
+        if response.status() != StatusCode::UNAUTHORIZED {
+            return check(response).await;
+        }
+        self.sign_in().await?;
+        let response = self.authorize(request(&self.http)).await.send().await?;
+        check(response).await
+    }
+
+    async fn authorize(&self, request: RequestBuilder) -> RequestBuilder {
+        match self.access_token.lock().await.as_deref() {
+            Some(token) => {
+                request.header(COOKIE, format!("{ACCESS_TOKEN}={token}"))
+            }
+            None => request,
+        }
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}{path}", self.base_url)
+    }
+}
+
+async fn check(response: Response) -> Result<Response> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+    let body = response.bytes().await?;
+    let message = serde_json::from_slice::<ErrorBody>(&body)
+        .map(|body| body.error)
+        .unwrap_or_else(|_| String::from_utf8_lossy(&body).into_owned());
+    Err(Error::Api { status, message })
+}
+
+async fn decode<T: DeserializeOwned>(response: Response) -> Result<T> {
+    let body = response.bytes().await?;
+    serde_json::from_slice(&body).map_err(Error::from)
+}
+
+fn access_token(response: &Response) -> Result<String> {
+    response
+        .headers()
+        .get_all(SET_COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .filter_map(|cookie| cookie.split(';').next())
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(name, _)| name.trim() == ACCESS_TOKEN)
+        .map(|(_, value)| value.trim().to_owned())
+        .ok_or(Error::MissingToken)
+}
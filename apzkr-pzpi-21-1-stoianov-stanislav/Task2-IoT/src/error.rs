@@ -0,0 +1,41 @@
+use reqwest::StatusCode;
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("request failed: {0}")]
+    Transport(#[from] reqwest::Error),
+    #[error("server responded with {status}: {message}")]
+    Api { status: StatusCode, message: String },
+    #[error("failed to decode response: {0}")]
+    Decode(#[from] serde_json::Error),
+    #[error("sign in did not return an access token")]
+    MissingToken,
+    #[error("journal is unavailable: {0}")]
+    Journal(#[from] std::io::Error),
+    #[error("journal entry {line} is corrupted: {source}")]
+    CorruptedJournal {
+        line: usize,
+        source: serde_json::Error,
+    },
+}
+
+impl Error {
+    pub fn is_offline(&self) -> bool {
+        match self {
+            Self::Transport(e) => e.is_connect() || e.is_timeout(),
+            Self::Api { status, .. } => matches!(
+                *status,
+                StatusCode::BAD_GATEWAY
+                    | StatusCode::SERVICE_UNAVAILABLE
+                    | StatusCode::GATEWAY_TIMEOUT
+            ),
+            _ => false,
+        }
+    }
+
+    pub fn is_server_error(&self) -> bool {
+        matches!(self, Self::Api { status, .. } if status.is_server_error())
+    }
+}
@@ -0,0 +1,133 @@
+use std::{
+    collections::{HashMap, HashSet},
+    path::PathBuf,
+};
+
+use serde::{Deserialize, Serialize};
+use tokio::{
+    fs::{self, OpenOptions},
+    io::AsyncWriteExt,
+};
+use uuid::Uuid;
+
+use crate::{
+    error::{Error, Result},
+    models::{LendRequest, ReturnRequest},
+};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum Operation {
+    #[serde(rename_all = "camelCase")]
+    Lend {
+        library_id: String,
+        request: LendRequest,
+    },
+    #[serde(rename_all = "camelCase")]
+    Return {
+        library_id: String,
+        request: ReturnRequest,
+    },
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "entry", rename_all = "camelCase")]
+enum Entry {
+    Queued { id: Uuid, operation: Operation },
+    Synced { id: Uuid },
+    Failed { id: Uuid },
+}
+
+#[derive(Clone, Debug)]
+pub struct Pending {
+    pub id: Uuid,
+    pub operation: Operation,
+    pub failures: usize,
+}
+
+#[derive(Clone, Debug)]
+pub struct Journal {
+    path: PathBuf,
+}
+
+impl Journal {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    pub async fn queue(&self, id: Uuid, operation: Operation) -> Result<()> {
+        self.append(&Entry::Queued { id, operation }).await
+    }
+
+    pub async fn mark_synced(&self, id: Uuid) -> Result<()> {
+        self.append(&Entry::Synced { id }).await
+    }
+
+    pub async fn mark_failed(&self, id: Uuid) -> Result<()> {
+        self.append(&Entry::Failed { id }).await
+    }
+
+    pub async fn pending(&self) -> Result<Vec<Pending>> {
+        let journal = match fs::read_to_string(&self.path).await {
+            Ok(journal) => journal,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                return Ok(Vec::new())
+            }
+            Err(e) => return Err(e.into()),
+        };
+        let mut queued = Vec::new();
+        let mut synced = HashSet::new();
+        let mut failures = HashMap::<_, usize>::new();
+        for (line, entry) in journal.lines().enumerate() {
+            if entry.trim().is_empty() {
+                continue;
+            }
+            match serde_json::from_str(entry).map_err(|source| {
+                Error::CorruptedJournal {
+                    line: line + 1,
+                    source,
+                }
+            })? {
+                Entry::Queued { id, operation } => queued.push((id, operation)),
+                Entry::Synced { id } => {
+                    synced.insert(id);
+                }
+                Entry::Failed { id } => *failures.entry(id).or_default() += 1,
+            }
+        }
+        Ok(queued
+            .into_iter()
+            .filter(|(id, _)| !synced.contains(id))
+            .map(|(id, operation)| Pending {
+                id,
+                operation,
+                failures: failures.get(&id).copied().unwrap_or_default(),
+            })
+            .collect())
+    }
+
+    pub async fn compact(&self) -> Result<()> {
+        if self.pending().await?.is_empty() {
+            match fs::remove_file(&self.path).await {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                    return Err(e.into())
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    async fn append(&self, entry: &Entry) -> Result<()> {
+        let mut line = serde_json::to_vec(entry)?;
+        line.push(b'\n');
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .await?;
+        file.write_all(&line).await?;
+        file.sync_data().await?;
+        Ok(())
+    }
+}
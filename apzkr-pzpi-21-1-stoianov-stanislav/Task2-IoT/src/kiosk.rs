@@ -0,0 +1,143 @@
+use chrono::Local;
+use uuid::Uuid;
+
+use crate::{
+    client::Client,
+    error::{Error, Result},
+    journal::{Journal, Operation, Pending},
+    models::{LendRequest, ReturnRequest, ReturnedLending},
+};
+
+/// Server errors other than gateway ones are retried on this many syncs
+/// before the operation is rejected.
+const MAX_ATTEMPTS: usize = 5;
+
+#[derive(Clone, Debug)]
+pub enum Outcome<T> {
+    Completed(T),
+    Queued,
+}
+
+#[derive(Debug, Default)]
+pub struct SyncReport {
+    pub synced: usize,
+    pub rejected: Vec<(Operation, Error)>,
+    pub pending: usize,
+}
+
+#[derive(Debug)]
+pub struct Kiosk {
+    client: Client,
+    journal: Journal,
+}
+
+impl Kiosk {
+    pub fn new(client: Client, journal: Journal) -> Self {
+        Self { client, journal }
+    }
+
+    pub fn client(&self) -> &Client {
+        &self.client
+    }
+
+    pub async fn lend_book(
+        &self,
+        library_id: &str,
+        mut request: LendRequest,
+    ) -> Result<Outcome<()>> {
+        let id = *request.operation_id.get_or_insert_with(Uuid::new_v4);
+        let result = match self.journal.pending().await?.is_empty() {
+            true => Some(self.client.lend_book(library_id, &request).await),
+            false => None,
+        };
+        let operation = Operation::Lend {
+            library_id: library_id.to_owned(),
+            request,
+        };
+        self.settle(id, operation, result).await
+    }
+
+    pub async fn return_book(
+        &self,
+        library_id: &str,
+        mut request: ReturnRequest,
+    ) -> Result<Outcome<ReturnedLending>> {
+        let id = *request.operation_id.get_or_insert_with(Uuid::new_v4);
+        request
+            .returned_on
+            .get_or_insert_with(|| Local::now().date_naive());
+        let result = match self.journal.pending().await?.is_empty() {
+            true => Some(self.client.return_book(library_id, &request).await),
+            false => None,
+        };
+        let operation = Operation::Return {
+            library_id: library_id.to_owned(),
+            request,
+        };
+        self.settle(id, operation, result).await
+    }
+
+    pub async fn sync(&self) -> Result<SyncReport> {
+        let mut report = SyncReport::default();
+        let mut pending = self.journal.pending().await?.into_iter();
+        for Pending {
+            id,
+            operation,
+            failures,
+        } in pending.by_ref()
+        {
+            match self.send(&operation).await {
+                Err(e) if e.is_offline() => {
+                    report.pending = 1;
+                    break;
+                }
+                Err(e)
+                    if e.is_server_error() && failures + 1 < MAX_ATTEMPTS =>
+                {
+                    self.journal.mark_failed(id).await?;
+                    report.pending = 1;
+                    break;
+                }
+                Ok(_) => report.synced += 1,
+                Err(e) => report.rejected.push((operation, e)),
+            }
+            self.journal.mark_synced(id).await?;
+        }
+        report.pending += pending.len();
+        self.journal.compact().await?;
+        Ok(report)
+    }
+
+    async fn settle<T>(
+        &self,
+        id: Uuid,
+        operation: Operation,
+        result: Option<Result<T>>,
+    ) -> Result<Outcome<T>> {
+        match result {
+            Some(Err(e)) if !e.is_offline() => Err(e),
+            Some(Ok(value)) => Ok(Outcome::Completed(value)),
+            _ => {
+                self.journal.queue(id, operation).await?;
+                Ok(Outcome::Queued)
+            }
+        }
+    }
+
+    async fn send(&self, operation: &Operation) -> Result<()> {
+        match operation {
+            Operation::Lend {
+                library_id,
+                request,
+            } => self.client.lend_book(library_id, request).await,
+            Operation::Return {
+                library_id,
+                request,
+            } => self
+                .client
+                .return_book(library_id, request)
+                .await
+                .map(|_| ()),
+        }
+    }
+}
@@ -0,0 +1,83 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+const MIN_THRESHOLD: u64 = 5;
+const MAX_LEND: u64 = 14;
+
+#[derive(Clone, Debug)]
+pub enum Credentials {
+    Password { email: String, password: String },
+    ApiKey(String),
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LendRequest {
+    pub lendee_id: String,
+    pub book_id: Option<String>,
+    pub copy_id: Option<String>,
+    pub lent_on: NaiveDate,
+    pub lent_for: u64,
+    pub operation_id: Option<Uuid>,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReturnRequest {
+    pub book_id: Option<String>,
+    pub copy_id: Option<String>,
+    pub lending_id: Option<String>,
+    pub returned_on: Option<NaiveDate>,
+    pub operation_id: Option<Uuid>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReturnedLending {
+    pub id: String,
+    pub returned_on: NaiveDate,
+    pub charge: Charge,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Charge {
+    pub lending_fee: String,
+    pub overdue_fee: String,
+    pub total: String,
+    pub currency: String,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LendingScore {
+    pub book_id: String,
+    pub score: u64,
+    pub popularity: u64,
+    pub total_lendings: u64,
+    pub average_lateness_days: f64,
+    pub queue_length: u64,
+}
+
+impl LendingScore {
+    pub fn days_to_lend(&self) -> u64 {
+        match self.score {
+            s if s < MIN_THRESHOLD => MAX_LEND - s,
+            s if s < MAX_LEND => MAX_LEND / s,
+            s => (MAX_LEND + s) / MIN_THRESHOLD,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct SignInRequest<'a> {
+    pub email: &'a str,
+    pub password: &'a str,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub(crate) struct ErrorBody {
+    pub error: String,
+}
@@ -1,27 +1,41 @@
-import React, { useEffect, useState } from "react";
+import React, { useCallback, useEffect, useState } from "react";
 import { Link, useNavigate } from "react-router-dom";
 import { useLocale } from "../locale";
 import Library from "./Library";
 
 function LibrariesPage() {
   const [libraries, setLibraries] = useState([]);
+  const [nextCursor, setNextCursor] = useState(null);
   const navigate = useNavigate();
   const locale = useLocale();
 
+  const loadLibraries = useCallback(
+    (cursor) => {
+      const query = cursor ? `?cursor=${encodeURIComponent(cursor)}` : "";
+      fetch(`http://localhost:8080/libraries${query}`)
+        .then((response) => {
+          if (response.status === 401 || response.status === 403) {
+            navigate("/login");
+          }
+          if (response.ok) {
+            return response.json();
+          }
+          throw new Error("Failed to fetch libraries");
+        })
+        .then((data) => {
+          setLibraries((libraries) =>
+            cursor ? [...libraries, ...data.items] : data.items
+          );
+          setNextCursor(data.nextCursor);
+        })
+        .catch((error) => console.log(error.message));
+    },
+    [navigate]
+  );
+
   useEffect(() => {
-    fetch("http://localhost:8080/libraries")
-      .then((response) => {
-        if (response.status === 401 || response.status === 403) {
-          navigate("/login");
-        }
-        if (response.ok) {
-          return response.json();
-        }
-        throw new Error("Failed to fetch libraries");
-      })
-      .then((data) => setLibraries(data))
-      .catch((error) => console.log(error.message));
-  }, [navigate]);
+    loadLibraries(null);
+  }, [loadLibraries]);
 
   return (
     <div className="p-4">
@@ -41,6 +55,16 @@ function LibrariesPage() {
           <Library library={library} />
         ))}
       </div>
+      {nextCursor && (
+        <div className="mt-4">
+          <button
+            onClick={() => loadLibraries(nextCursor)}
+            className="bg-blue-500 text-white px-4 py-2 rounded"
+          >
+            {locale.loadMore}
+          </button>
+        </div>
+      )}
     </div>
   );
 }
@@ -1,6 +1,7 @@
 import React, { useEffect, useState } from "react";
 import { useParams, useNavigate } from "react-router-dom";
 import { useLocale } from "../locale";
+import { fetchAllUsers } from "../users";
 
 function LibraryDetails() {
   const { id } = useParams();
@@ -30,14 +31,8 @@ function LibraryDetails() {
       .then((data) => setLibrary(data))
       .catch((error) => console.log(error.message));
 
-    fetch("http://localhost:8080/auth/users", { credentials: "include" })
-      .then((response) => {
-        if (!response.ok) {
-          throw new Error("Failed to fetch users");
-        }
-        return response.json();
-      })
-      .then((data) => setUsers(data))
+    fetchAllUsers()
+      .then(setUsers)
       .catch((error) => console.log(error.message));
   }, [id, navigate]);
 
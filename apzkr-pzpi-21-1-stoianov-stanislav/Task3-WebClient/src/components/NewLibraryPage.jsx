@@ -1,6 +1,7 @@
 import React, { useState, useEffect } from "react";
 import { useNavigate } from "react-router-dom";
 import { useLocale } from "../locale";
+import { fetchAllUsers } from "../users";
 
 function NewLibrary() {
   const navigate = useNavigate();
@@ -16,14 +17,8 @@ function NewLibrary() {
   const locale = useLocale();
 
   useEffect(() => {
-    fetch("http://localhost:8080/auth/users", { credentials: "include" })
-      .then((response) => {
-        if (!response.ok) {
-          throw new Error("Failed to fetch users");
-        }
-        return response.json();
-      })
-      .then((data) => setUsers(data))
+    fetchAllUsers()
+      .then(setUsers)
       .catch((error) => console.log(error.message));
   }, []);
 
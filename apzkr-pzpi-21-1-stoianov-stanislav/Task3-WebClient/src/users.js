@@ -0,0 +1,19 @@
+// Loads every user by following `nextCursor` until the last page.
+export async function fetchAllUsers() {
+  const users = [];
+  let cursor = null;
+  do {
+    const query = cursor ? `&cursor=${encodeURIComponent(cursor)}` : "";
+    const response = await fetch(
+      `http://localhost:8080/auth/users?limit=100${query}`,
+      { credentials: "include" }
+    );
+    if (!response.ok) {
+      throw new Error("Failed to fetch users");
+    }
+    const data = await response.json();
+    users.push(...data.items);
+    cursor = data.nextCursor;
+  } while (cursor);
+  return users;
+}